@@ -8,10 +8,10 @@ use pprof::criterion::{Output, PProfProfiler};
 
 use orchard::{
     builder::Builder,
-    bundle::Flags,
+    bundle::{BatchValidator, Flags},
     circuit::{ProvingKey, VerifyingKey},
     keys::{FullViewingKey, SpendingKey},
-    value::NoteValue,
+    value::{NoteValue, ValueSum},
     Anchor, Bundle,
 };
 use rand::rngs::OsRng;
@@ -35,7 +35,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                 .add_recipient(None, recipient, NoteValue::from_raw(10), None)
                 .unwrap();
         }
-        let bundle: Bundle<_, i64> = builder.build(rng).unwrap();
+        let (bundle, _): (Bundle<_, i64>, _) = builder.build(rng).unwrap();
 
         let instances: Vec<_> = bundle
             .actions()
@@ -67,6 +67,19 @@ fn criterion_benchmark(c: &mut Criterion) {
             group.bench_function(BenchmarkId::new("bundle", num_recipients), |b| {
                 b.iter(|| bundle.authorization().proof().verify(&vk, &instances));
             });
+
+            let bundle = bundle
+                .try_map_value_balance(|v| Ok::<_, ()>(ValueSum::from_raw(v)))
+                .unwrap();
+            group.bench_function(BenchmarkId::new("batch-of-10", num_recipients), |b| {
+                b.iter(|| {
+                    let mut validator = BatchValidator::new();
+                    for _ in 0..10 {
+                        validator.add_bundle(&bundle, [0; 32]);
+                    }
+                    validator.validate(&vk, rng)
+                });
+            });
         }
     }
 }
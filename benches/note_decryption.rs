@@ -52,7 +52,7 @@ fn bench_note_decryption(c: &mut Criterion) {
         builder
             .add_recipient(None, recipient, NoteValue::from_raw(10), None)
             .unwrap();
-        let bundle: Bundle<_, i64> = builder.build(rng).unwrap();
+        let (bundle, _): (Bundle<_, i64>, _) = builder.build(rng).unwrap();
         bundle
             .create_proof(&pk)
             .unwrap()
@@ -139,12 +139,73 @@ fn bench_note_decryption(c: &mut Criterion) {
     }
 }
 
+fn bench_compact_scan(c: &mut Criterion) {
+    let rng = OsRng;
+    let pk = ProvingKey::build();
+
+    let fvk = FullViewingKey::from(&SpendingKey::from_bytes([7; 32]).unwrap());
+    let recipient = fvk.default_address();
+
+    // Emulate a wallet with two pools of funds, neither of which receives any of the
+    // scanned notes.
+    let ivks: Vec<_> = (0u32..2)
+        .map(|i| {
+            let mut sk = [0; 32];
+            sk[..4].copy_from_slice(&i.to_le_bytes());
+            IncomingViewingKey::from(&FullViewingKey::from(&SpendingKey::from_bytes(sk).unwrap()))
+        })
+        .collect();
+
+    let bundle = {
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            Anchor::from_bytes([0; 32]).unwrap(),
+        );
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(10), None)
+            .unwrap();
+        let (bundle, _): (Bundle<_, i64>, _) = builder.build(rng).unwrap();
+        bundle
+            .create_proof(&pk)
+            .unwrap()
+            .apply_signatures(rng, [0; 32], &[])
+            .unwrap()
+    };
+    let action = bundle.actions().first();
+
+    let mut group = c.benchmark_group("compact-scan");
+    group.sample_size(10);
+
+    for size in array::IntoIter::new([1_000, 10_000, 100_000]) {
+        let compact: Vec<_> = (0..size).map(|_| CompactAction::from(action)).collect();
+
+        group.throughput(Throughput::Elements((ivks.len() * size) as u64));
+        group.bench_function(BenchmarkId::new("parallel", size), |b| {
+            b.iter(|| CompactAction::scan(&compact, &ivks))
+        });
+
+        let compact: Vec<_> = compact
+            .into_iter()
+            .map(|compact| (OrchardDomain::for_action(action), compact))
+            .collect();
+        group.bench_function(BenchmarkId::new("sequential", size), |b| {
+            b.iter(|| {
+                for ivk in &ivks {
+                    for (domain, compact) in &compact {
+                        try_compact_note_decryption(domain, ivk, compact);
+                    }
+                }
+            })
+        });
+    }
+}
+
 #[cfg(unix)]
 criterion_group! {
     name = benches;
     config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-    targets = bench_note_decryption
+    targets = bench_note_decryption, bench_compact_scan
 }
 #[cfg(not(unix))]
-criterion_group!(benches, bench_note_decryption);
+criterion_group!(benches, bench_note_decryption, bench_compact_scan);
 criterion_main!(benches);
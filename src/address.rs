@@ -1,3 +1,7 @@
+use std::convert::TryInto;
+
+use subtle::CtOption;
+
 use crate::{
     keys::{DiversifiedTransmissionKey, Diversifier},
     spec::{diversify_hash, NonIdentityPallasPoint},
@@ -28,7 +32,8 @@ impl Address {
         Address { d, pk_d }
     }
 
-    pub(crate) fn diversifer(&self) -> Diversifier {
+    /// Returns the [`Diversifier`] for this `Address`.
+    pub fn diversifier(&self) -> Diversifier {
         self.d
     }
 
@@ -39,6 +44,30 @@ impl Address {
     pub(crate) fn pk_d(&self) -> &DiversifiedTransmissionKey {
         &self.pk_d
     }
+
+    /// Serializes this address to its "raw" encoding as specified in
+    /// [Zcash Protocol Spec § 5.6.4.2: Orchard Raw Payment Addresses][orchardpaymentaddrencoding].
+    ///
+    /// [orchardpaymentaddrencoding]: https://zips.z.cash/protocol/nu5.pdf#orchardpaymentaddrencoding
+    pub fn to_raw_address_bytes(&self) -> [u8; 43] {
+        let mut result = [0u8; 43];
+        result[..11].copy_from_slice(self.d.as_array());
+        result[11..].copy_from_slice(&self.pk_d.to_bytes());
+        result
+    }
+
+    /// Parses an address from its "raw" encoding as specified in
+    /// [Zcash Protocol Spec § 5.6.4.2: Orchard Raw Payment Addresses][orchardpaymentaddrencoding].
+    ///
+    /// Returns `None` if $\mathsf{pk_d}$ is not a valid non-identity Pallas point.
+    ///
+    /// [orchardpaymentaddrencoding]: https://zips.z.cash/protocol/nu5.pdf#orchardpaymentaddrencoding
+    pub fn from_raw_address_bytes(bytes: &[u8; 43]) -> CtOption<Self> {
+        DiversifiedTransmissionKey::from_bytes(bytes[11..].try_into().unwrap()).map(|pk_d| {
+            let d = Diversifier::from_bytes(bytes[..11].try_into().unwrap());
+            Self::from_parts(d, pk_d)
+        })
+    }
 }
 
 /// Generators for property testing.
@@ -58,3 +87,31 @@ pub mod testing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{testing::arb_address, Address};
+
+    proptest! {
+        #[test]
+        fn raw_encoding_round_trip(addr in arb_address()) {
+            let bytes = addr.to_raw_address_bytes();
+            assert_eq!(&bytes[..11], addr.diversifier().as_array());
+            assert_eq!(Address::from_raw_address_bytes(&bytes).unwrap(), addr);
+        }
+    }
+
+    #[test]
+    fn raw_encoding_rejects_invalid_pk_d() {
+        // The identity is encoded as all-zeroes, and is not a valid pk_d.
+        assert!(bool::from(
+            Address::from_raw_address_bytes(&[0; 43]).is_none()
+        ));
+        // Non-canonical point encoding.
+        assert!(bool::from(
+            Address::from_raw_address_bytes(&[0xff; 43]).is_none()
+        ));
+    }
+}
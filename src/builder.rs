@@ -1,21 +1,25 @@
 //! Logic for building Orchard components of transactions.
 
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::iter;
 
+use blake2b_simd::Params as Blake2bParams;
 use ff::Field;
 use group::GroupEncoding;
 use nonempty::NonEmpty;
 use pasta_curves::pallas;
-use rand::{CryptoRng, RngCore};
+use rand::{seq::SliceRandom, CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
 use crate::{
     address::Address,
     bundle::{Action, Authorization, Authorized, Bundle, Flags},
-    circuit::{Circuit, Instance, Proof, ProvingKey},
+    circuit::{Circuit, Instance, OrchardProver, Proof},
     keys::{
-        FullViewingKey, OutgoingViewingKey, SpendAuthorizingKey, SpendValidatingKey, SpendingKey,
+        FullViewingKey, OutgoingViewingKey, Scope, SpendAuthorizingKey, SpendValidatingKey,
+        SpendingKey,
     },
+    memo::MemoBytes,
     note::{Note, TransmittedNoteCiphertext},
     note_encryption::OrchardNoteEncryption,
     primitives::redpallas::{self, Binding, SpendAuth},
@@ -23,13 +27,46 @@ use crate::{
     value::{self, NoteValue, OverflowError, ValueCommitTrapdoor, ValueCommitment, ValueSum},
 };
 
+mod encoding;
+pub use encoding::{SerializableProof, SerializableSignatures};
+
+mod signer;
+pub use signer::{DisplayInfo, OrchardSigner, SigningRequest, SoftwareSigner};
+
 const MIN_ACTIONS: usize = 2;
 
+const BUILD_RNG_PERSONALIZATION: &[u8; 16] = b"ZcashOrchardBRNG";
+
 /// An error type for the kinds of errors that can occur during bundle construction.
 #[derive(Debug)]
 pub enum Error {
+    /// A note could not be spent because spends are disabled for this bundle.
+    SpendsDisabled,
+    /// A note could not be spent because its Merkle path does not lead to the anchor of
+    /// this bundle.
+    AnchorMismatch,
+    /// A note could not be spent because it was not sent to an address derived from the
+    /// given full viewing key.
+    WrongSpendingKey,
+    /// The spent notes do not cover the outputs and the target value balance. Contains
+    /// the value that is missing.
+    InsufficientFunds(ValueSum),
+    /// A change output is required, but no change address was set and no notes are being
+    /// spent.
+    MissingChangeAddress,
+    /// A recipient or change output was requested, but outputs are disabled for this
+    /// bundle.
+    OutputsDisabled,
     /// A bundle could not be built because required signatures were missing.
     MissingSignatures,
+    /// An externally-created signature is not valid for any unsigned action.
+    InvalidExternalSignature,
+    /// An externally-created signature is valid for more than one unsigned action.
+    DuplicateSignature,
+    /// The display information given to an external signer does not match the bundle.
+    InvalidDisplayInfo,
+    /// An external signer failed to create signatures.
+    Signer(Box<dyn std::error::Error + Send + Sync>),
     /// An error occurred in the process of producing a proof for a bundle.
     Proof(halo2::plonk::Error),
     /// An overflow error occurred while attempting to construct the value
@@ -54,6 +91,7 @@ impl From<value::OverflowError> for Error {
 struct SpendInfo {
     dummy_sk: Option<SpendingKey>,
     fvk: FullViewingKey,
+    scope: Scope,
     note: Note,
     merkle_path: MerklePath,
 }
@@ -69,6 +107,8 @@ impl SpendInfo {
         SpendInfo {
             dummy_sk: Some(sk),
             fvk,
+            // We use external scope to avoid unnecessary derivations.
+            scope: Scope::External,
             note,
             merkle_path,
         }
@@ -81,7 +121,7 @@ struct RecipientInfo {
     ovk: Option<OutgoingViewingKey>,
     recipient: Address,
     value: NoteValue,
-    memo: Option<[u8; 512]>,
+    memo: Option<MemoBytes>,
 }
 
 impl RecipientInfo {
@@ -128,20 +168,25 @@ impl ActionInfo {
     /// Defined in [Zcash Protocol Spec § 4.7.3: Sending Notes (Orchard)][orchardsend].
     ///
     /// [orchardsend]: https://zips.z.cash/protocol/nu5.pdf#orchardsend
-    fn build(self, mut rng: impl RngCore) -> (Action<SigningMetadata>, Circuit) {
+    fn build(self, rngs: &mut BuildRngs) -> (Action<SigningMetadata>, Circuit) {
         let v_net = self.value_sum().expect("already checked this");
         let cv_net = ValueCommitment::derive(v_net, self.rcv.clone());
 
         let nf_old = self.spend.note.nullifier(&self.spend.fvk);
-        let sender_address = self.spend.fvk.default_address();
+        let sender_address = self.spend.note.recipient();
         let rho_old = self.spend.note.rho();
         let psi_old = self.spend.note.rseed().psi(&rho_old);
         let rcm_old = self.spend.note.rseed().rcm(&rho_old);
         let ak: SpendValidatingKey = self.spend.fvk.clone().into();
-        let alpha = pallas::Scalar::random(&mut rng);
+        let alpha = pallas::Scalar::random(&mut rngs.alpha);
         let rk = ak.randomize(&alpha);
 
-        let note = Note::new(self.output.recipient, self.output.value, nf_old, &mut rng);
+        let note = Note::new(
+            self.output.recipient,
+            self.output.value,
+            nf_old,
+            &mut rngs.rseed,
+        );
         let cm_new = note.commitment();
         let cmx = cm_new.into();
 
@@ -149,17 +194,17 @@ impl ActionInfo {
             self.output.ovk,
             note,
             self.output.recipient,
-            self.output.memo.unwrap_or_else(|| {
-                let mut memo = [0; 512];
-                memo[0] = 0xf6;
-                memo
-            }),
+            self.output.memo.unwrap_or_else(MemoBytes::empty),
         );
 
         let encrypted_note = TransmittedNoteCiphertext {
             epk_bytes: encryptor.epk().to_bytes().0,
             enc_ciphertext: encryptor.encrypt_note_plaintext(),
-            out_ciphertext: encryptor.encrypt_outgoing_plaintext(&cv_net, &cmx, &mut rng),
+            out_ciphertext: encryptor.encrypt_outgoing_plaintext(
+                &cv_net,
+                &cmx,
+                &mut rngs.out_ciphertext,
+            ),
         };
 
         (
@@ -190,7 +235,7 @@ impl ActionInfo {
                 alpha: Some(alpha),
                 ak: Some(ak),
                 nk: Some(*self.spend.fvk.nk()),
-                rivk: Some(*self.spend.fvk.rivk()),
+                rivk: Some(self.spend.fvk.rivk(self.spend.scope)),
                 g_d_new_star: Some((*note.recipient().g_d()).to_bytes()),
                 pk_d_new_star: Some(note.recipient().pk_d().to_bytes()),
                 v_new: Some(note.value()),
@@ -202,12 +247,66 @@ impl ActionInfo {
     }
 }
 
+/// The independent random streams used while building a bundle.
+///
+/// Each stream is a [`ChaCha20Rng`] seeded with
+/// `BLAKE2b-256(personalization = "ZcashOrchardBRNG", seed || tag)`, where `tag` is the
+/// ASCII name of the stream. This ensures that changing how much randomness one step
+/// consumes does not affect the values produced by any other step.
+#[derive(Debug)]
+struct BuildRngs {
+    /// Tag `"dummy_spends"`: the keys, notes and Merkle paths of dummy spends, in the
+    /// order they are created.
+    dummy_spends: ChaCha20Rng,
+    /// Tag `"dummy_outputs"`: the recipients of dummy outputs, in the order they are
+    /// created.
+    dummy_outputs: ChaCha20Rng,
+    /// Tag `"shuffle"`: the shuffle of the spends, followed by the shuffle of the outputs.
+    shuffle: ChaCha20Rng,
+    /// Tag `"rcv"`: the value commitment trapdoor of each action, in action order.
+    rcv: ChaCha20Rng,
+    /// Tag `"alpha"`: the spend authorization randomizer of each action, in action order.
+    alpha: ChaCha20Rng,
+    /// Tag `"rseed"`: the `rseed` of each output note, in action order. The ephemeral
+    /// secret key of each output is derived from its `rseed`.
+    rseed: ChaCha20Rng,
+    /// Tag `"out_ciphertext"`: the randomness used to create the outgoing ciphertexts of
+    /// outputs without an outgoing viewing key, in action order.
+    out_ciphertext: ChaCha20Rng,
+}
+
+impl BuildRngs {
+    fn from_seed(seed: [u8; 32]) -> Self {
+        let stream = |tag: &[u8]| {
+            let mut h = Blake2bParams::new()
+                .hash_length(32)
+                .personal(BUILD_RNG_PERSONALIZATION)
+                .to_state();
+            h.update(&seed);
+            h.update(tag);
+            ChaCha20Rng::from_seed(h.finalize().as_bytes().try_into().unwrap())
+        };
+
+        BuildRngs {
+            dummy_spends: stream(b"dummy_spends"),
+            dummy_outputs: stream(b"dummy_outputs"),
+            shuffle: stream(b"shuffle"),
+            rcv: stream(b"rcv"),
+            alpha: stream(b"alpha"),
+            rseed: stream(b"rseed"),
+            out_ciphertext: stream(b"out_ciphertext"),
+        }
+    }
+}
+
 /// A builder that constructs a [`Bundle`] from a set of notes to be spent, and recipients
 /// to receive funds.
 #[derive(Debug)]
 pub struct Builder {
     spends: Vec<SpendInfo>,
     recipients: Vec<RecipientInfo>,
+    change_address: Option<(Option<OutgoingViewingKey>, Address)>,
+    target_value_balance: Option<ValueSum>,
     flags: Flags,
     anchor: Anchor,
 }
@@ -218,6 +317,8 @@ impl Builder {
         Builder {
             spends: vec![],
             recipients: vec![],
+            change_address: None,
+            target_value_balance: None,
             flags,
             anchor,
         }
@@ -225,29 +326,36 @@ impl Builder {
 
     /// Adds a note to be spent in this transaction.
     ///
-    /// Returns an error if the given Merkle path does not have the required anchor for
-    /// the given note.
+    /// Returns an error if spends are disabled for this builder, if the given Merkle path
+    /// does not have the required anchor for the given note, or if the note was not sent
+    /// to an address derived from `fvk`.
     pub fn add_spend(
         &mut self,
         fvk: FullViewingKey,
         note: Note,
         merkle_path: MerklePath,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), Error> {
         if !self.flags.spends_enabled() {
-            return Err("Spends are not enabled for this builder");
+            return Err(Error::SpendsDisabled);
         }
 
         // Consistency check: all anchors must be equal.
         let cm = note.commitment();
-        let path_root: Anchor =
-            <Option<_>>::from(merkle_path.root(cm.into())).ok_or("Derived the bottom anchor")?;
-        if path_root != self.anchor {
-            return Err("All anchors must be equal.");
+        let path_root: Option<Anchor> = merkle_path.root(cm.into()).into();
+        if path_root != Some(self.anchor) {
+            return Err(Error::AnchorMismatch);
         }
 
+        // The note may have been sent to an address in either scope of the full viewing
+        // key, and the circuit needs the matching rivk.
+        let scope = fvk
+            .scope_for_address(&note.recipient())
+            .ok_or(Error::WrongSpendingKey)?;
+
         self.spends.push(SpendInfo {
             dummy_sk: None,
             fvk,
+            scope,
             note,
             merkle_path,
         });
@@ -256,15 +364,18 @@ impl Builder {
     }
 
     /// Adds an address which will receive funds in this transaction.
+    ///
+    /// If `memo` is `None`, the recipient receives an empty memo ([`MemoBytes::empty`]).
+    /// A [`Memo`](crate::memo::Memo) can be converted with `MemoBytes::from`.
     pub fn add_recipient(
         &mut self,
         ovk: Option<OutgoingViewingKey>,
         recipient: Address,
         value: NoteValue,
-        memo: Option<[u8; 512]>,
-    ) -> Result<(), &'static str> {
+        memo: Option<MemoBytes>,
+    ) -> Result<(), Error> {
         if !self.flags.outputs_enabled() {
-            return Err("Outputs are not enabled for this builder");
+            return Err(Error::OutputsDisabled);
         }
 
         self.recipients.push(RecipientInfo {
@@ -277,42 +388,192 @@ impl Builder {
         Ok(())
     }
 
+    /// Sets the address that receives change when a target value balance has been set
+    /// with [`Builder::set_target_value_balance`].
+    ///
+    /// If no change address is set, change is sent to the internal address at index 0 of
+    /// the full viewing key for the first note added with [`Builder::add_spend`].
+    pub fn set_change_address(&mut self, ovk: Option<OutgoingViewingKey>, address: Address) {
+        self.change_address = Some((ovk, address));
+    }
+
+    /// Sets the value balance that the bundle should have.
+    ///
+    /// The value balance is the net value leaving the Orchard pool. For a transaction
+    /// that only spends and creates Orchard notes, this is the transaction fee.
+    ///
+    /// When the bundle is built, any value spent in excess of the recipients' values and
+    /// this target is sent to the change address in an additional output. Building fails
+    /// with [`Error::InsufficientFunds`] if the spent notes are not sufficient.
+    pub fn set_target_value_balance(&mut self, value_balance: ValueSum) {
+        self.target_value_balance = Some(value_balance);
+    }
+
+    /// Adds an output that sends any value in excess of `target` to the change address.
+    fn add_change_output(&mut self, target: ValueSum) -> Result<(), Error> {
+        let value_balance = self
+            .spends
+            .iter()
+            .map(|spend| spend.note.value() - NoteValue::zero())
+            .chain(
+                self.recipients
+                    .iter()
+                    .map(|recipient| NoteValue::zero() - recipient.value),
+            )
+            .fold(Some(ValueSum::zero()), |acc, value| acc? + value?)
+            .ok_or(OverflowError)?;
+
+        let change = match (value_balance - target)
+            .ok_or(OverflowError)?
+            .to_note_value()
+        {
+            Some(change) => change,
+            None => {
+                return Err(Error::InsufficientFunds(
+                    (target - value_balance).ok_or(OverflowError)?,
+                ))
+            }
+        };
+        if change.inner() == 0 {
+            return Ok(());
+        }
+        if !self.flags.outputs_enabled() {
+            return Err(Error::OutputsDisabled);
+        }
+
+        let (ovk, recipient) = match self.change_address.take() {
+            Some(change_address) => change_address,
+            None => {
+                let fvk = &self.spends.first().ok_or(Error::MissingChangeAddress)?.fvk;
+                (
+                    Some(fvk.to_ovk(Scope::Internal)),
+                    fvk.address_at(0u32, Scope::Internal),
+                )
+            }
+        };
+
+        self.recipients.push(RecipientInfo {
+            ovk,
+            recipient,
+            value: change,
+            memo: None,
+        });
+
+        Ok(())
+    }
+
     /// Builds a bundle containing the given spent notes and recipients.
     ///
-    /// This API assumes that none of the notes being spent are controlled by (threshold)
-    /// multisignatures, and immediately constructs the bundle proof.
+    /// The spends and recipients are padded with dummies as necessary, and then placed
+    /// into actions in a random order, so that the positions of the actions do not reveal
+    /// which of them are real. The returned [`BundleMetadata`] maps each spend and
+    /// recipient (in the order they were added to the builder) to its action index.
+    ///
+    /// If a target value balance was set and a change output was added, the change
+    /// output follows the recipients in the [`BundleMetadata`].
+    ///
+    /// Notes controlled by (threshold) multisignatures can be signed outside of the
+    /// builder, for example with [`frost`], and the signatures added with
+    /// [`Bundle::append_signatures`].
+    ///
+    /// [`frost`]: crate::primitives::redpallas::frost
     pub fn build<V: TryFrom<i64>>(
-        mut self,
+        self,
         mut rng: impl RngCore,
-    ) -> Result<Bundle<InProgress<Unproven, Unauthorized>, V>, Error> {
-        // Pair up the spends and recipients, extending with dummy values as necessary.
-        //
-        // TODO: Do we want to shuffle the order like we do for Sapling? And if we do, do
-        // we need the extra logic for mapping the user-provided input order to the
-        // shuffled order?
-        let pre_actions: Vec<_> = {
-            let num_spends = self.spends.len();
-            let num_recipients = self.recipients.len();
-            let num_actions = [num_spends, num_recipients, MIN_ACTIONS]
-                .iter()
-                .max()
-                .cloned()
-                .unwrap();
+    ) -> Result<
+        (
+            Bundle<InProgress<Unproven, Unauthorized>, V>,
+            BundleMetadata,
+        ),
+        Error,
+    > {
+        let mut seed = [0; 32];
+        rng.fill_bytes(&mut seed);
+        self.build_deterministic(seed)
+    }
 
-            self.spends.extend(
-                iter::repeat_with(|| SpendInfo::dummy(&mut rng)).take(num_actions - num_spends),
-            );
-            self.recipients.extend(
-                iter::repeat_with(|| RecipientInfo::dummy(&mut rng))
-                    .take(num_actions - num_recipients),
-            );
+    /// Builds a bundle as for [`Builder::build`], deriving all randomness from `seed`.
+    ///
+    /// Building the same inputs with the same seed always produces the same bundle. This
+    /// is intended for test vectors, golden-file tests and reproducing bugs; the seed
+    /// must be kept secret and never reused for bundles that are broadcast, as it
+    /// determines the value commitment trapdoors and spend authorization randomizers.
+    ///
+    /// Each use of randomness (dummy spends, dummy outputs, the shuffle of spends and
+    /// outputs, and the `rcv`, `alpha`, `rseed` and outgoing ciphertext randomness of
+    /// each action) is drawn from its own ChaCha20 stream. Each stream is seeded with
+    /// `BLAKE2b-256(personalization = "ZcashOrchardBRNG", seed || tag)`, where `tag` is
+    /// one of the ASCII strings `"dummy_spends"`, `"dummy_outputs"`, `"shuffle"`,
+    /// `"rcv"`, `"alpha"`, `"rseed"` and `"out_ciphertext"` respectively.
+    ///
+    /// The proof and signatures created from the returned bundle are not covered by the
+    /// seed: proofs are always randomized, and signatures use the RNG passed to
+    /// [`Bundle::prepare`] and [`Bundle::sign`].
+    pub fn build_deterministic<V: TryFrom<i64>>(
+        mut self,
+        seed: [u8; 32],
+    ) -> Result<
+        (
+            Bundle<InProgress<Unproven, Unauthorized>, V>,
+            BundleMetadata,
+        ),
+        Error,
+    > {
+        let mut rngs = BuildRngs::from_seed(seed);
+
+        if let Some(target) = self.target_value_balance {
+            self.add_change_output(target)?;
+        }
 
-            self.spends
-                .into_iter()
-                .zip(self.recipients.into_iter())
-                .map(|(spend, recipient)| ActionInfo::new(spend, recipient, &mut rng))
-                .collect()
-        };
+        let num_real_spends = self.spends.len();
+        let num_real_recipients = self.recipients.len();
+        let num_actions = [num_real_spends, num_real_recipients, MIN_ACTIONS]
+            .iter()
+            .max()
+            .cloned()
+            .unwrap();
+
+        // Pad the spends and recipients with dummy values as necessary, and then shuffle
+        // them, remembering the original index of each one.
+        let mut indexed_spends: Vec<_> = self
+            .spends
+            .into_iter()
+            .chain(iter::repeat_with(|| {
+                SpendInfo::dummy(&mut rngs.dummy_spends)
+            }))
+            .take(num_actions)
+            .enumerate()
+            .collect();
+        let mut indexed_recipients: Vec<_> = self
+            .recipients
+            .into_iter()
+            .chain(iter::repeat_with(|| {
+                RecipientInfo::dummy(&mut rngs.dummy_outputs)
+            }))
+            .take(num_actions)
+            .enumerate()
+            .collect();
+        indexed_spends.shuffle(&mut rngs.shuffle);
+        indexed_recipients.shuffle(&mut rngs.shuffle);
+
+        // Pair up the spends and recipients, recording where the real ones ended up.
+        let mut metadata = BundleMetadata::new(num_real_spends, num_real_recipients);
+        let pre_actions: Vec<_> = indexed_spends
+            .into_iter()
+            .zip(indexed_recipients.into_iter())
+            .enumerate()
+            .map(
+                |(action_idx, ((spend_idx, spend), (recipient_idx, recipient)))| {
+                    if let Some(idx) = metadata.spend_indices.get_mut(spend_idx) {
+                        *idx = action_idx;
+                    }
+                    if let Some(idx) = metadata.output_indices.get_mut(recipient_idx) {
+                        *idx = action_idx;
+                    }
+                    ActionInfo::new(spend, recipient, &mut rngs.rcv)
+                },
+            )
+            .collect();
 
         // Move some things out of self that we will need.
         let flags = self.flags;
@@ -339,7 +600,7 @@ impl Builder {
 
         // Create the actions.
         let (actions, circuits): (Vec<_>, Vec<_>) =
-            pre_actions.into_iter().map(|a| a.build(&mut rng)).unzip();
+            pre_actions.into_iter().map(|a| a.build(&mut rngs)).unzip();
 
         // Verify that bsk and bvk are consistent.
         let bvk = (actions.iter().map(|a| a.cv_net()).sum::<ValueCommitment>()
@@ -347,19 +608,60 @@ impl Builder {
         .into_bvk();
         assert_eq!(redpallas::VerificationKey::from(&bsk), bvk);
 
-        Ok(Bundle::from_parts(
-            NonEmpty::from_vec(actions).unwrap(),
-            flags,
-            result_value_balance,
-            anchor,
-            InProgress {
-                proof: Unproven { circuits },
-                sigs: Unauthorized { bsk },
-            },
+        Ok((
+            Bundle::from_parts(
+                NonEmpty::from_vec(actions).unwrap(),
+                flags,
+                result_value_balance,
+                anchor,
+                InProgress {
+                    proof: Unproven { circuits },
+                    sigs: Unauthorized { bsk },
+                },
+            ),
+            metadata,
         ))
     }
 }
 
+/// Metadata about a bundle created by [`Builder::build`], that is not necessarily
+/// recoverable from the bundle itself.
+///
+/// This includes information about how [`Action`]s within the bundle are ordered (after
+/// padding and randomization) relative to the order in which spends and recipients were
+/// added to the builder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleMetadata {
+    spend_indices: Vec<usize>,
+    output_indices: Vec<usize>,
+}
+
+impl BundleMetadata {
+    fn new(num_requested_spends: usize, num_requested_outputs: usize) -> Self {
+        BundleMetadata {
+            spend_indices: vec![0; num_requested_spends],
+            output_indices: vec![0; num_requested_outputs],
+        }
+    }
+
+    /// Returns the index within the bundle of the [`Action`] corresponding to the `n`-th
+    /// spend added to the builder.
+    ///
+    /// Returns `None` if `n` is not the index of a spend that was added to the builder.
+    pub fn spend_action_index(&self, n: usize) -> Option<usize> {
+        self.spend_indices.get(n).copied()
+    }
+
+    /// Returns the index within the bundle of the [`Action`] corresponding to the `n`-th
+    /// recipient added to the builder.
+    ///
+    /// Returns `None` if `n` is not the index of a recipient that was added to the
+    /// builder.
+    pub fn output_action_index(&self, n: usize) -> Option<usize> {
+        self.output_indices.get(n).copied()
+    }
+}
+
 /// Marker trait representing bundle signatures in the process of being created.
 pub trait InProgressSignatures {
     /// The authorization type of an Orchard action in the process of being authorized.
@@ -389,16 +691,23 @@ impl<S: InProgressSignatures> InProgress<Unproven, S> {
     /// Creates the proof for this bundle.
     pub fn create_proof(
         &self,
-        pk: &ProvingKey,
+        prover: &impl OrchardProver,
         instances: &[Instance],
     ) -> Result<Proof, halo2::plonk::Error> {
-        Proof::create(pk, &self.proof.circuits, instances)
+        prover.create_proof(&self.proof.circuits, instances)
     }
 }
 
 impl<S: InProgressSignatures, V> Bundle<InProgress<Unproven, S>, V> {
-    /// Creates the proof for this bundle.
-    pub fn create_proof(self, pk: &ProvingKey) -> Result<Bundle<InProgress<Proof, S>, V>, Error> {
+    /// Creates the proof for this bundle with the given prover.
+    ///
+    /// A [`ProvingKey`] can be used to create the proof locally.
+    ///
+    /// [`ProvingKey`]: crate::circuit::ProvingKey
+    pub fn create_proof(
+        self,
+        prover: &impl OrchardProver,
+    ) -> Result<Bundle<InProgress<Proof, S>, V>, Error> {
         let instances: Vec<_> = self
             .actions()
             .iter()
@@ -408,7 +717,7 @@ impl<S: InProgressSignatures, V> Bundle<InProgress<Unproven, S>, V> {
             &mut (),
             |_, _, a| Ok(a),
             |_, auth| {
-                let proof = auth.create_proof(pk, &instances)?;
+                let proof = auth.create_proof(prover, &instances)?;
                 Ok(InProgress {
                     proof,
                     sigs: auth.sigs,
@@ -428,6 +737,19 @@ pub struct SigningParts {
     alpha: pallas::Scalar,
 }
 
+impl SigningParts {
+    /// Returns the spend validating key that must authorize this action.
+    pub fn ak(&self) -> &SpendValidatingKey {
+        &self.ak
+    }
+
+    /// Returns the randomizer $\alpha$ that the signature for this action must be
+    /// created with.
+    pub fn alpha(&self) -> pallas::Scalar {
+        self.alpha
+    }
+}
+
 /// Marker for an unauthorized bundle with no signatures.
 #[derive(Debug)]
 pub struct Unauthorized {
@@ -546,6 +868,63 @@ impl<P, V> Bundle<InProgress<P, PartiallyAuthorized>, V> {
             |_, partial| partial,
         )
     }
+
+    /// Returns the sighash that the spend authorization signatures must be created over.
+    pub fn sighash(&self) -> [u8; 32] {
+        self.authorization().sigs.sighash
+    }
+
+    /// Returns the index and signing parts of each action that is not yet signed.
+    ///
+    /// This can be used to create signatures outside of the builder, for example with a
+    /// threshold signing scheme.
+    pub fn unsigned_actions(&self) -> impl Iterator<Item = (usize, &SigningParts)> {
+        self.actions()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, action)| match action.authorization() {
+                MaybeSigned::SigningMetadata(parts) => Some((i, parts)),
+                MaybeSigned::Signature(_) => None,
+            })
+    }
+
+    /// Adds externally-created spend authorization signatures to this bundle.
+    ///
+    /// Each signature is applied to the unsigned action whose randomized verification key
+    /// it is valid for. Returns an error if a signature is not valid for exactly one
+    /// unsigned action.
+    pub fn append_signatures(
+        self,
+        signatures: &[redpallas::Signature<SpendAuth>],
+    ) -> Result<Self, Error> {
+        signatures.iter().try_fold(self, Self::append_signature)
+    }
+
+    fn append_signature(self, signature: &redpallas::Signature<SpendAuth>) -> Result<Self, Error> {
+        let mut signature_valid_for = 0usize;
+        let bundle = self.authorize(
+            &mut signature_valid_for,
+            |valid_for, partial, maybe| match maybe {
+                MaybeSigned::SigningMetadata(parts)
+                    if parts
+                        .ak
+                        .randomize(&parts.alpha)
+                        .verify(&partial.sigs.sighash, signature)
+                        .is_ok() =>
+                {
+                    *valid_for += 1;
+                    MaybeSigned::Signature(signature.clone())
+                }
+                s => s,
+            },
+            |_, partial| partial,
+        );
+        match signature_valid_for {
+            0 => Err(Error::InvalidExternalSignature),
+            1 => Ok(bundle),
+            _ => Err(Error::DuplicateSignature),
+        }
+    }
 }
 
 impl<V> Bundle<InProgress<Proof, PartiallyAuthorized>, V> {
@@ -584,7 +963,8 @@ pub mod testing {
             testing::arb_spending_key, FullViewingKey, OutgoingViewingKey, SpendAuthorizingKey,
             SpendingKey,
         },
-        tree::{testing::arb_tree, Anchor, MerklePath},
+        note::Nullifier,
+        tree::{Anchor, MerklePath, NoteCommitmentTree},
         value::{testing::arb_positive_note_value, NoteValue, MAX_NOTE_VALUE},
         Address, Note,
     };
@@ -627,9 +1007,8 @@ pub mod testing {
             }
 
             let pk = ProvingKey::build();
-            builder
-                .build(&mut self.rng)
-                .unwrap()
+            let (bundle, _) = builder.build(&mut self.rng).unwrap();
+            bundle
                 .create_proof(&pk)
                 .unwrap()
                 .prepare(&mut self.rng, [0; 32])
@@ -647,7 +1026,7 @@ pub mod testing {
             n_recipients in 1..30,
         )
         (
-            (notes_and_auth_paths, anchor) in arb_tree(n_notes),
+            note_values in vec(arb_positive_note_value(MAX_NOTE_VALUE / n_notes as u64), n_notes),
             recipient_amounts in vec(
                 arb_address().prop_flat_map(move |a| {
                     arb_positive_note_value(MAX_NOTE_VALUE / n_recipients as u64)
@@ -657,11 +1036,33 @@ pub mod testing {
             ),
             rng_seed in prop::array::uniform32(prop::num::u8::ANY)
         ) -> ArbitraryBundleInputs<StdRng> {
+            let mut rng = StdRng::from_seed(rng_seed);
+
+            // The spent notes must have been sent to the spending key.
+            let recipient = FullViewingKey::from(&sk).default_address();
+            let notes: Vec<_> = note_values
+                .into_iter()
+                .map(|value| Note::new(recipient, value, Nullifier::dummy(&mut rng), &mut rng))
+                .collect();
+
+            let mut tree = NoteCommitmentTree::new(0);
+            for note in &notes {
+                assert!(tree.append(&note.commitment().into()));
+                tree.mark();
+            }
+            let notes = notes
+                .into_iter()
+                .map(|note| {
+                    let path = tree.witness(&note.commitment().into()).unwrap();
+                    (note, path)
+                })
+                .collect();
+
             ArbitraryBundleInputs {
-                rng: StdRng::from_seed(rng_seed),
+                rng,
                 sk: sk.clone(),
-                anchor,
-                notes: notes_and_auth_paths,
+                anchor: tree.root(),
+                notes,
                 recipient_amounts
             }
         }
@@ -684,16 +1085,21 @@ pub mod testing {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+    use std::convert::{TryFrom, TryInto};
+
     use rand::rngs::OsRng;
 
-    use super::Builder;
+    use super::{Builder, Error};
     use crate::{
-        bundle::{Authorized, Bundle, Flags},
-        circuit::ProvingKey,
+        bundle::{write_action_without_auth, Authorized, Bundle, Flags},
+        circuit::{testing::FakeProver, ProvingKey},
         constants::MERKLE_DEPTH_ORCHARD,
-        keys::{FullViewingKey, SpendingKey},
-        tree::EMPTY_ROOTS,
-        value::NoteValue,
+        keys::{FullViewingKey, IncomingViewingKey, Scope, SpendingKey},
+        note::{ExtractedNoteCommitment, Note, Nullifier},
+        primitives::redpallas::frost,
+        tree::{NoteCommitmentTree, EMPTY_ROOTS},
+        value::{NoteValue, ValueSum},
     };
 
     #[test]
@@ -716,6 +1122,7 @@ mod tests {
         let bundle: Bundle<Authorized, i64> = builder
             .build(&mut rng)
             .unwrap()
+            .0
             .create_proof(&pk)
             .unwrap()
             .prepare(&mut rng, [0; 32])
@@ -723,4 +1130,281 @@ mod tests {
             .unwrap();
         assert_eq!(bundle.value_balance(), &(-5000))
     }
+
+    #[test]
+    fn fake_prover() {
+        let mut rng = OsRng;
+
+        let recipient = FullViewingKey::from(&SpendingKey::random(&mut rng)).default_address();
+
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+        );
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(5000), None)
+            .unwrap();
+
+        // The rest of the bundle can be constructed without creating a real proof.
+        let bundle: Bundle<Authorized, i64> = builder
+            .build(&mut rng)
+            .unwrap()
+            .0
+            .create_proof(&FakeProver)
+            .unwrap()
+            .prepare(&mut rng, [0; 32])
+            .finalize()
+            .unwrap();
+        assert_eq!(bundle.value_balance(), &(-5000));
+        assert!(bundle.authorization().proof().as_ref().is_empty());
+    }
+
+    #[test]
+    fn spend_validation() {
+        let mut rng = OsRng;
+
+        let (_, fvk, note) = Note::dummy(&mut rng, None);
+        let cmx: ExtractedNoteCommitment = note.commitment().into();
+        let mut tree = NoteCommitmentTree::new(0);
+        tree.append(&cmx);
+        tree.mark();
+
+        let mut builder = Builder::new(Flags::from_parts(false, true), tree.root());
+        assert!(matches!(
+            builder.add_spend(fvk.clone(), note, tree.witness(&cmx).unwrap()),
+            Err(Error::SpendsDisabled)
+        ));
+
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+        );
+        assert!(matches!(
+            builder.add_spend(fvk.clone(), note, tree.witness(&cmx).unwrap()),
+            Err(Error::AnchorMismatch)
+        ));
+
+        let mut builder = Builder::new(Flags::from_parts(true, true), tree.root());
+        let other_fvk = FullViewingKey::from(&SpendingKey::random(&mut rng));
+        assert!(matches!(
+            builder.add_spend(other_fvk, note, tree.witness(&cmx).unwrap()),
+            Err(Error::WrongSpendingKey)
+        ));
+
+        // The note can be spent with the full viewing key it was sent to.
+        assert!(builder
+            .add_spend(fvk, note, tree.witness(&cmx).unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn change_output() {
+        let mut rng = OsRng;
+
+        let fvk = FullViewingKey::from(&SpendingKey::random(&mut rng));
+        let note = Note::new(
+            fvk.default_address(),
+            NoteValue::from_raw(10_000),
+            Nullifier::dummy(&mut rng),
+            &mut rng,
+        );
+        let cmx: ExtractedNoteCommitment = note.commitment().into();
+        let mut tree = NoteCommitmentTree::new(0);
+        tree.append(&cmx);
+        tree.mark();
+        let recipient = FullViewingKey::from(&SpendingKey::random(&mut rng)).default_address();
+
+        let builder = |target: i64| {
+            let mut builder = Builder::new(Flags::from_parts(true, true), tree.root());
+            builder
+                .add_spend(fvk.clone(), note, tree.witness(&cmx).unwrap())
+                .unwrap();
+            builder
+                .add_recipient(None, recipient, NoteValue::from_raw(3000), None)
+                .unwrap();
+            builder.set_target_value_balance(ValueSum::from_raw(target));
+            builder
+        };
+
+        // The excess value is sent to the internal address of the spending key.
+        let (bundle, metadata): (Bundle<_, i64>, _) = builder(1000).build(&mut rng).unwrap();
+        assert_eq!(bundle.value_balance(), &1000);
+        let decrypted = bundle.decrypt_outputs_with_keys(&[fvk.to_ivk(Scope::Internal)]);
+        assert_eq!(decrypted.len(), 1);
+        let (action_idx, _, change, address, _) = &decrypted[0];
+        assert_eq!(change.value().inner(), 6000);
+        assert_eq!(address, &fvk.address_at(0u32, Scope::Internal));
+        assert_eq!(metadata.output_action_index(1), Some(*action_idx));
+
+        // No change output is needed if the value is spent exactly.
+        let (bundle, metadata): (Bundle<_, i64>, _) = builder(7000).build(&mut rng).unwrap();
+        assert_eq!(bundle.value_balance(), &7000);
+        assert_eq!(metadata.output_action_index(1), None);
+
+        assert!(matches!(
+            builder(8000).build::<i64>(&mut rng),
+            Err(Error::InsufficientFunds(missing)) if missing == ValueSum::from_raw(1000)
+        ));
+
+        // Without any spends, there is no default change address.
+        let mut builder = Builder::new(Flags::from_parts(true, true), tree.root());
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(3000), None)
+            .unwrap();
+        builder.set_target_value_balance(ValueSum::from_raw(-5000));
+        assert!(matches!(
+            builder.build::<i64>(&mut rng),
+            Err(Error::MissingChangeAddress)
+        ));
+
+        // Recipients cannot be added if outputs are disabled.
+        let mut builder = Builder::new(Flags::from_parts(true, false), tree.root());
+        assert!(matches!(
+            builder.add_recipient(None, recipient, NoteValue::from_raw(3000), None),
+            Err(Error::OutputsDisabled)
+        ));
+    }
+
+    #[test]
+    fn deterministic_build() {
+        let mut rng = OsRng;
+        let recipient = FullViewingKey::from(&SpendingKey::random(&mut rng)).default_address();
+
+        let build = |seed| {
+            let mut builder = Builder::new(
+                Flags::from_parts(true, true),
+                EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+            );
+            builder
+                .add_recipient(None, recipient, NoteValue::from_raw(5000), None)
+                .unwrap();
+            let (bundle, _): (Bundle<_, i64>, _) = builder.build_deterministic(seed).unwrap();
+            bundle
+                .actions()
+                .iter()
+                .map(|action| {
+                    let mut encoded = vec![];
+                    write_action_without_auth(action, &mut encoded).unwrap();
+                    encoded
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(build([1; 32]), build([1; 32]));
+        assert_ne!(build([1; 32]), build([2; 32]));
+    }
+
+    #[test]
+    fn frost_spend() {
+        let mut rng = OsRng;
+
+        // A note controlled by a 2-of-3 FROST group.
+        let (shares, public_key_package) = frost::generate_with_dealer(3, 2, &mut rng).unwrap();
+        let key_packages: BTreeMap<_, frost::KeyPackage> = shares
+            .into_iter()
+            .map(|(id, share)| (id, share.try_into().unwrap()))
+            .collect();
+        let mut fvk_bytes = FullViewingKey::from(&SpendingKey::random(&mut rng)).to_bytes();
+        fvk_bytes[..32].copy_from_slice(&<[u8; 32]>::from(public_key_package.group_public()));
+        let fvk = FullViewingKey::from_bytes(&fvk_bytes).unwrap();
+
+        let note = Note::new(
+            fvk.default_address(),
+            NoteValue::from_raw(10_000),
+            Nullifier::dummy(&mut rng),
+            &mut rng,
+        );
+        let cmx: ExtractedNoteCommitment = note.commitment().into();
+        let mut tree = NoteCommitmentTree::new(0);
+        tree.append(&cmx);
+        tree.mark();
+
+        let mut builder = Builder::new(Flags::from_parts(true, true), tree.root());
+        builder
+            .add_spend(fvk, note, tree.witness(&cmx).unwrap())
+            .unwrap();
+        let (bundle, _): (Bundle<_, i64>, _) = builder.build(&mut rng).unwrap();
+        let sighash = [0x42; 32];
+        let bundle = bundle
+            .create_proof(&FakeProver)
+            .unwrap()
+            .prepare(&mut rng, sighash);
+
+        // Only the real spend is left to sign.
+        let alphas: Vec<_> = bundle
+            .unsigned_actions()
+            .map(|(_, parts)| parts.alpha())
+            .collect();
+        assert_eq!(alphas.len(), 1);
+
+        let signatures: Vec<_> = alphas
+            .into_iter()
+            .map(|alpha| {
+                let signers = [1, 3]
+                    .iter()
+                    .map(|&id| frost::Identifier::try_from(id).unwrap());
+                let (nonces, commitments): (BTreeMap<_, _>, BTreeMap<_, _>) = signers
+                    .map(|id| {
+                        let (nonces, commitments) = frost::commit(&key_packages[&id], &mut rng);
+                        ((id, nonces), (id, commitments))
+                    })
+                    .unzip();
+                let signing_package =
+                    frost::SigningPackage::new(commitments, &bundle.sighash(), alpha);
+                let shares = nonces
+                    .into_iter()
+                    .map(|(id, nonces)| {
+                        let share =
+                            frost::sign(&signing_package, nonces, &key_packages[&id]).unwrap();
+                        (id, share)
+                    })
+                    .collect();
+                frost::aggregate(&signing_package, &shares, &public_key_package).unwrap()
+            })
+            .collect();
+
+        let bundle = bundle.append_signatures(&signatures).unwrap();
+        assert_eq!(bundle.unsigned_actions().count(), 0);
+
+        // A signature that doesn't match any unsigned action is rejected.
+        assert!(matches!(
+            bundle.append_signatures(&signatures),
+            Err(Error::InvalidExternalSignature)
+        ));
+    }
+
+    #[test]
+    fn bundle_metadata() {
+        let mut rng = OsRng;
+
+        let fvk = FullViewingKey::from(&SpendingKey::random(&mut rng));
+        let ivk = IncomingViewingKey::from(&fvk);
+
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+        );
+        for value in 1..=3 {
+            builder
+                .add_recipient(
+                    None,
+                    fvk.default_address(),
+                    NoteValue::from_raw(value),
+                    None,
+                )
+                .unwrap();
+        }
+        let (bundle, metadata): (Bundle<_, i64>, _) = builder.build(&mut rng).unwrap();
+        assert_eq!(bundle.actions().len(), 3);
+
+        // Each recipient can be found at the action index given by the metadata.
+        let decrypted = bundle.decrypt_outputs_with_keys(&[ivk]);
+        assert_eq!(decrypted.len(), 3);
+        for (action_idx, _, note, _, _) in decrypted {
+            let n = note.value().inner() as usize - 1;
+            assert_eq!(metadata.output_action_index(n), Some(action_idx));
+        }
+        assert_eq!(metadata.output_action_index(3), None);
+        assert_eq!(metadata.spend_action_index(0), None);
+    }
 }
@@ -0,0 +1,397 @@
+//! Serialization of bundles that are in the process of being built.
+//!
+//! This enables the steps of creating a bundle to be split across several parties: for
+//! example, a wallet can build an unproven bundle, hand it to a separate prover, and then
+//! pass the proven bundle to one or more signers holding the spend authorizing keys.
+//!
+//! The encoding of an in-progress bundle contains secret data: the circuit witnesses
+//! of an [`Unproven`] bundle, the binding signing key of an [`Unauthorized`] bundle, and
+//! the spend authorizing keys of dummy spends. It should only be shared with parties
+//! that are trusted with the spent notes.
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+use halo2::arithmetic::FieldExt;
+use nonempty::NonEmpty;
+use pasta_curves::pallas;
+
+use super::{
+    InProgress, InProgressSignatures, MaybeSigned, PartiallyAuthorized, SigningMetadata,
+    SigningParts, Unauthorized, Unproven,
+};
+use crate::{
+    bundle::{read_action_without_auth, write_action_without_auth, Bundle, Flags, MAX_ACTIONS},
+    circuit::{Circuit, Proof},
+    keys::{SpendAuthorizingKey, SpendValidatingKey},
+    primitives::redpallas,
+    serialization::{
+        invalid_data, read_bytes, read_compact_size_bounded, write_compact_size, MAX_COMPACT_SIZE,
+    },
+    tree::Anchor,
+};
+
+/// The version of the in-progress bundle encoding.
+const VERSION: u8 = 1;
+
+/// The proof state of an in-progress bundle that can be serialized.
+///
+/// This trait is sealed, and is implemented for [`Unproven`] and [`Proof`].
+pub trait SerializableProof: private::SealedProof {}
+
+/// The signature state of an in-progress bundle that can be serialized.
+///
+/// This trait is sealed, and is implemented for [`Unauthorized`] and
+/// [`PartiallyAuthorized`].
+pub trait SerializableSignatures: private::SealedSignatures {}
+
+mod private {
+    use std::io::{self, Read, Write};
+
+    use super::InProgressSignatures;
+
+    pub trait SealedProof: Sized {
+        /// The byte identifying this proof state in an encoding.
+        const TAG: u8;
+
+        fn write_proof<W: Write>(&self, writer: W) -> io::Result<()>;
+
+        fn read_proof<R: Read>(reader: R, n_actions: usize) -> io::Result<Self>;
+    }
+
+    pub trait SealedSignatures: InProgressSignatures + Sized {
+        /// The byte identifying this signature state in an encoding.
+        const TAG: u8;
+
+        fn write_spend_auth<W: Write>(auth: &Self::SpendAuth, writer: W) -> io::Result<()>;
+
+        fn read_spend_auth<R: Read>(reader: R) -> io::Result<Self::SpendAuth>;
+
+        fn write_sigs<W: Write>(&self, writer: W) -> io::Result<()>;
+
+        fn read_sigs<R: Read>(reader: R) -> io::Result<Self>;
+    }
+}
+
+use private::{SealedProof, SealedSignatures};
+
+impl SerializableProof for Unproven {}
+
+impl SealedProof for Unproven {
+    const TAG: u8 = 0;
+
+    fn write_proof<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for circuit in &self.circuits {
+            circuit.write(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    fn read_proof<R: Read>(mut reader: R, n_actions: usize) -> io::Result<Self> {
+        let circuits = (0..n_actions)
+            .map(|_| Circuit::read(&mut reader))
+            .collect::<Result<_, _>>()?;
+        Ok(Unproven { circuits })
+    }
+}
+
+impl SerializableProof for Proof {}
+
+impl SealedProof for Proof {
+    const TAG: u8 = 1;
+
+    fn write_proof<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_compact_size(&mut writer, self.as_ref().len() as u64)?;
+        writer.write_all(self.as_ref())
+    }
+
+    fn read_proof<R: Read>(mut reader: R, _: usize) -> io::Result<Self> {
+        let proof_len = read_compact_size_bounded(&mut reader, MAX_COMPACT_SIZE)?;
+        let mut proof = vec![];
+        reader.take(proof_len as u64).read_to_end(&mut proof)?;
+        if proof.len() != proof_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "proof is truncated",
+            ));
+        }
+        Ok(Proof::new(proof))
+    }
+}
+
+impl SerializableSignatures for Unauthorized {}
+
+impl SealedSignatures for Unauthorized {
+    const TAG: u8 = 0;
+
+    fn write_spend_auth<W: Write>(auth: &SigningMetadata, mut writer: W) -> io::Result<()> {
+        match &auth.dummy_ask {
+            None => writer.write_all(&[0])?,
+            Some(ask) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&ask.to_bytes())?;
+            }
+        }
+        write_signing_parts(&auth.parts, writer)
+    }
+
+    fn read_spend_auth<R: Read>(mut reader: R) -> io::Result<SigningMetadata> {
+        let dummy_ask = match read_bytes::<_, 1>(&mut reader)?[0] {
+            0 => None,
+            1 => Some(
+                SpendAuthorizingKey::from_bytes(&read_bytes(&mut reader)?)
+                    .ok_or_else(|| invalid_data("invalid spend authorizing key"))?,
+            ),
+            _ => return Err(invalid_data("invalid presence flag for dummy spend key")),
+        };
+        let parts = read_signing_parts(reader)?;
+        Ok(SigningMetadata { dummy_ask, parts })
+    }
+
+    fn write_sigs<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&<[u8; 32]>::from(&self.bsk))
+    }
+
+    fn read_sigs<R: Read>(mut reader: R) -> io::Result<Self> {
+        let bsk = redpallas::SigningKey::try_from(read_bytes::<_, 32>(&mut reader)?)
+            .map_err(|_| invalid_data("invalid binding signing key"))?;
+        Ok(Unauthorized { bsk })
+    }
+}
+
+impl SerializableSignatures for PartiallyAuthorized {}
+
+impl SealedSignatures for PartiallyAuthorized {
+    const TAG: u8 = 1;
+
+    fn write_spend_auth<W: Write>(auth: &MaybeSigned, mut writer: W) -> io::Result<()> {
+        match auth {
+            MaybeSigned::SigningMetadata(parts) => {
+                writer.write_all(&[0])?;
+                write_signing_parts(parts, writer)
+            }
+            MaybeSigned::Signature(sig) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&<[u8; 64]>::from(sig))
+            }
+        }
+    }
+
+    fn read_spend_auth<R: Read>(mut reader: R) -> io::Result<MaybeSigned> {
+        match read_bytes::<_, 1>(&mut reader)?[0] {
+            0 => read_signing_parts(reader).map(MaybeSigned::SigningMetadata),
+            1 => Ok(MaybeSigned::Signature(redpallas::Signature::from(
+                read_bytes::<_, 64>(reader)?,
+            ))),
+            _ => Err(invalid_data("invalid spend authorization state")),
+        }
+    }
+
+    fn write_sigs<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&<[u8; 64]>::from(&self.binding_signature))?;
+        writer.write_all(&self.sighash)
+    }
+
+    fn read_sigs<R: Read>(mut reader: R) -> io::Result<Self> {
+        let binding_signature = redpallas::Signature::from(read_bytes::<_, 64>(&mut reader)?);
+        let sighash = read_bytes(&mut reader)?;
+        Ok(PartiallyAuthorized {
+            binding_signature,
+            sighash,
+        })
+    }
+}
+
+fn write_signing_parts<W: Write>(parts: &SigningParts, mut writer: W) -> io::Result<()> {
+    writer.write_all(&parts.ak.to_bytes())?;
+    writer.write_all(&parts.alpha.to_bytes())
+}
+
+fn read_signing_parts<R: Read>(mut reader: R) -> io::Result<SigningParts> {
+    let ak = SpendValidatingKey::from_bytes(&read_bytes(&mut reader)?)
+        .ok_or_else(|| invalid_data("invalid spend validating key"))?;
+    let alpha = Option::from(pallas::Scalar::from_bytes(&read_bytes(&mut reader)?))
+        .ok_or_else(|| invalid_data("non-canonical spend authorization randomizer"))?;
+    Ok(SigningParts { ak, alpha })
+}
+
+impl<P: SerializableProof, S: SerializableSignatures, V> Bundle<InProgress<P, S>, V> {
+    /// Writes this in-progress bundle, so that it can be passed to another party to
+    /// continue its construction.
+    ///
+    /// The encoding identifies the proof and signature state of the bundle, and can be
+    /// read back with [`Bundle::read`] for the same [`InProgress`] type.
+    pub fn write<'a, W: Write>(&'a self, mut writer: W) -> io::Result<()>
+    where
+        i64: From<&'a V>,
+    {
+        writer.write_all(&[VERSION, P::TAG, S::TAG])?;
+
+        write_compact_size(&mut writer, self.actions().len() as u64)?;
+        for action in self.actions().iter() {
+            write_action_without_auth(action, &mut writer)?;
+            S::write_spend_auth(action.authorization(), &mut writer)?;
+        }
+
+        writer.write_all(&[self.flags().to_byte()])?;
+        writer.write_all(&i64::from(self.value_balance()).to_le_bytes())?;
+        writer.write_all(&self.anchor().to_bytes())?;
+
+        self.authorization().proof.write_proof(&mut writer)?;
+        self.authorization().sigs.write_sigs(&mut writer)
+    }
+
+    /// Reads an in-progress bundle written by [`Bundle::write`].
+    ///
+    /// Returns an error if the encoding is for a bundle in a different proof or signature
+    /// state, if any field is not canonically encoded, or if the value balance cannot be
+    /// represented as a `V`.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self>
+    where
+        V: TryFrom<i64>,
+    {
+        let [version, proof_tag, sigs_tag] = read_bytes::<_, 3>(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid_data("unsupported in-progress bundle version"));
+        }
+        if proof_tag != P::TAG || sigs_tag != S::TAG {
+            return Err(invalid_data(
+                "in-progress bundle is in a different proof or signature state",
+            ));
+        }
+
+        let n_actions = read_compact_size_bounded(&mut reader, MAX_ACTIONS)?;
+        // We don't preallocate based on the untrusted action count.
+        let mut actions = vec![];
+        for _ in 0..n_actions {
+            let action = read_action_without_auth(&mut reader)?;
+            let auth = S::read_spend_auth(&mut reader)?;
+            actions.push(action.map(|()| auth));
+        }
+        let actions =
+            NonEmpty::from_vec(actions).ok_or_else(|| invalid_data("bundle has no actions"))?;
+
+        let flags = Flags::from_byte(read_bytes::<_, 1>(&mut reader)?[0])?;
+        let value_balance = V::try_from(i64::from_le_bytes(read_bytes(&mut reader)?))
+            .map_err(|_| invalid_data("value balance out of range"))?;
+        let anchor = Anchor::from_bytes(read_bytes(&mut reader)?)
+            .ok_or_else(|| invalid_data("non-canonical anchor"))?;
+
+        let proof = P::read_proof(&mut reader, n_actions)?;
+        let sigs = S::read_sigs(&mut reader)?;
+
+        Ok(Bundle::from_parts(
+            actions,
+            flags,
+            value_balance,
+            anchor,
+            InProgress { proof, sigs },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+
+    use crate::{
+        builder::{Builder, InProgress, PartiallyAuthorized, Unauthorized, Unproven},
+        bundle::{BatchValidator, Bundle, Flags},
+        circuit::{Proof, ProvingKey, VerifyingKey},
+        keys::{FullViewingKey, SpendAuthorizingKey, SpendingKey},
+        note::{ExtractedNoteCommitment, Note},
+        tree::NoteCommitmentTree,
+        value::{NoteValue, ValueSum},
+    };
+
+    /// A value balance type that can be written by [`Bundle::write`].
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Amount(i64);
+
+    impl From<i64> for Amount {
+        fn from(v: i64) -> Self {
+            Amount(v)
+        }
+    }
+
+    impl From<&Amount> for i64 {
+        fn from(v: &Amount) -> Self {
+            v.0
+        }
+    }
+
+    impl From<Amount> for ValueSum {
+        fn from(v: Amount) -> Self {
+            ValueSum::from_raw(v.0)
+        }
+    }
+
+    fn round_trip<P, S>(
+        bundle: &Bundle<InProgress<P, S>, Amount>,
+    ) -> Bundle<InProgress<P, S>, Amount>
+    where
+        P: super::SerializableProof,
+        S: super::SerializableSignatures,
+    {
+        let mut encoded = vec![];
+        bundle.write(&mut encoded).unwrap();
+        let decoded = Bundle::<InProgress<P, S>, Amount>::read(&encoded[..]).unwrap();
+
+        let mut reencoded = vec![];
+        decoded.write(&mut reencoded).unwrap();
+        assert_eq!(encoded, reencoded);
+
+        // Truncated encodings are rejected.
+        assert!(Bundle::<InProgress<P, S>, Amount>::read(&encoded[..encoded.len() - 1]).is_err());
+
+        decoded
+    }
+
+    #[test]
+    fn in_progress_bundle_round_trip() {
+        let pk = ProvingKey::build();
+        let vk = VerifyingKey::build();
+        let mut rng = OsRng;
+
+        // A note that we can spend, and its position in the commitment tree.
+        let (sk, fvk, note) = Note::dummy(&mut rng, None);
+        let cmx: ExtractedNoteCommitment = note.commitment().into();
+        let mut tree = NoteCommitmentTree::new(10);
+        tree.append(&cmx);
+        tree.mark();
+        let merkle_path = tree.witness(&cmx).unwrap();
+
+        let recipient = FullViewingKey::from(&SpendingKey::random(&mut rng)).default_address();
+
+        let mut builder = Builder::new(Flags::from_parts(true, true), tree.root());
+        builder.add_spend(fvk, note, merkle_path).unwrap();
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(0), None)
+            .unwrap();
+        let (unproven, _): (Bundle<_, Amount>, _) = builder.build(&mut rng).unwrap();
+
+        let unproven = round_trip(&unproven);
+
+        // The witnesses survive the round trip, so the bundle can be proven.
+        let proven = round_trip(&unproven.create_proof(&pk).unwrap());
+
+        // The encoding identifies the state of the bundle.
+        let mut encoded = vec![];
+        proven.write(&mut encoded).unwrap();
+        assert!(Bundle::<InProgress<Unproven, Unauthorized>, Amount>::read(&encoded[..]).is_err());
+        assert!(
+            Bundle::<InProgress<Proof, PartiallyAuthorized>, Amount>::read(&encoded[..]).is_err()
+        );
+
+        let sighash = [7; 32];
+        let partial = round_trip(&proven.prepare(&mut rng, sighash));
+
+        // The real spend still needs to be signed.
+        let signed = round_trip(&partial.sign(&mut rng, &SpendAuthorizingKey::from(&sk)));
+        let bundle = signed.finalize().unwrap();
+
+        let mut validator = BatchValidator::new();
+        validator.add_bundle(&bundle, sighash);
+        assert_eq!(validator.validate(&vk, OsRng), Ok(()));
+    }
+}
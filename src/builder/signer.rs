@@ -0,0 +1,327 @@
+//! Creation of spend authorization signatures outside of the builder.
+
+use std::convert::Infallible;
+
+use rand::{CryptoRng, RngCore};
+
+use super::{Error, InProgress, PartiallyAuthorized, SigningParts};
+use crate::{
+    address::Address,
+    bundle::Bundle,
+    keys::{SpendAuthorizingKey, SpendValidatingKey},
+    memo::MemoBytes,
+    primitives::redpallas::{self, SpendAuth},
+    value::{NoteValue, ValueSum},
+};
+
+/// Information about a bundle that a signer can display to its user before authorizing
+/// the bundle's spends.
+///
+/// This information is provided by the caller, typically from
+/// [`Bundle::decrypt_outputs_with_keys`]. [`Bundle::sign_with`] checks that the value
+/// balance matches the bundle and that each output refers to an action in the bundle,
+/// but it cannot check the recipient, value or memo of an output against the action's
+/// ciphertext. A signer that does not trust the host providing this information must
+/// decrypt the outputs itself before displaying them.
+#[derive(Debug, Default)]
+pub struct DisplayInfo {
+    value_balance: Option<ValueSum>,
+    outputs: Vec<(usize, Address, NoteValue, MemoBytes)>,
+}
+
+impl DisplayInfo {
+    /// Constructs an empty set of display information.
+    pub fn new() -> Self {
+        DisplayInfo::default()
+    }
+
+    /// Sets the value balance of the bundle.
+    pub fn with_value_balance(mut self, value_balance: ValueSum) -> Self {
+        self.value_balance = Some(value_balance);
+        self
+    }
+
+    /// Adds an output of the action at `action_index`.
+    pub fn with_output(
+        mut self,
+        action_index: usize,
+        recipient: Address,
+        value: NoteValue,
+        memo: MemoBytes,
+    ) -> Self {
+        self.outputs.push((action_index, recipient, value, memo));
+        self
+    }
+
+    /// Returns the value balance of the bundle, if it was provided.
+    pub fn value_balance(&self) -> Option<ValueSum> {
+        self.value_balance
+    }
+
+    /// Returns the action index, recipient, value and memo of each provided output.
+    pub fn outputs(&self) -> &[(usize, Address, NoteValue, MemoBytes)] {
+        &self.outputs
+    }
+}
+
+/// A request for the spend authorization signatures of a bundle.
+#[derive(Debug)]
+pub struct SigningRequest<'a> {
+    sighash: [u8; 32],
+    spends: Vec<(usize, &'a SigningParts)>,
+    display: DisplayInfo,
+}
+
+impl<'a> SigningRequest<'a> {
+    /// Returns the sighash that the signatures must be created over.
+    pub fn sighash(&self) -> [u8; 32] {
+        self.sighash
+    }
+
+    /// Returns the action index and signing parts of each action that still needs a
+    /// signature.
+    pub fn spends(&self) -> &[(usize, &'a SigningParts)] {
+        &self.spends
+    }
+
+    /// Returns the information about the bundle that can be displayed to the user.
+    pub fn display_info(&self) -> &DisplayInfo {
+        &self.display
+    }
+}
+
+/// A signer that holds spend authorizing keys outside of the builder, such as a hardware
+/// wallet.
+pub trait OrchardSigner {
+    /// The type of errors produced by this signer.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Creates spend authorization signatures for the requested spends.
+    ///
+    /// The signer should return a signature for each spend that it controls, created
+    /// with the spend's randomizer $\alpha$ over the request's sighash. Spends that it
+    /// does not control can be skipped.
+    fn sign(
+        &mut self,
+        request: &SigningRequest<'_>,
+    ) -> Result<Vec<redpallas::Signature<SpendAuth>>, Self::Error>;
+}
+
+/// An [`OrchardSigner`] that holds spend authorizing keys in memory.
+#[derive(Debug)]
+pub struct SoftwareSigner<R> {
+    keys: Vec<(SpendValidatingKey, SpendAuthorizingKey)>,
+    rng: R,
+}
+
+impl<R: RngCore + CryptoRng> SoftwareSigner<R> {
+    /// Constructs a signer for the given spend authorizing keys.
+    pub fn new(keys: Vec<SpendAuthorizingKey>, rng: R) -> Self {
+        SoftwareSigner {
+            keys: keys
+                .into_iter()
+                .map(|ask| (SpendValidatingKey::from(&ask), ask))
+                .collect(),
+            rng,
+        }
+    }
+}
+
+impl<R: RngCore + CryptoRng> OrchardSigner for SoftwareSigner<R> {
+    type Error = Infallible;
+
+    fn sign(
+        &mut self,
+        request: &SigningRequest<'_>,
+    ) -> Result<Vec<redpallas::Signature<SpendAuth>>, Self::Error> {
+        let sighash = request.sighash();
+        let keys = &self.keys;
+        let rng = &mut self.rng;
+        Ok(request
+            .spends()
+            .iter()
+            .filter_map(|(_, parts)| {
+                keys.iter()
+                    .find(|(ak, _)| ak == parts.ak())
+                    .map(|(_, ask)| ask.randomize(&parts.alpha()).sign(&mut *rng, &sighash))
+            })
+            .collect())
+    }
+}
+
+impl<P, V: Copy + Into<i64>> Bundle<InProgress<P, PartiallyAuthorized>, V> {
+    /// Requests spend authorization signatures from the given signer, and adds them to
+    /// this bundle.
+    ///
+    /// Returns [`Error::InvalidDisplayInfo`] if `display` has a value balance that
+    /// differs from the bundle's, or an output for an action that is not in the bundle.
+    ///
+    /// The signatures are checked with [`Bundle::append_signatures`] before they are
+    /// added, so a faulty or malicious signer cannot corrupt the bundle.
+    pub fn sign_with<S: OrchardSigner>(
+        self,
+        signer: &mut S,
+        display: DisplayInfo,
+    ) -> Result<Self, Error> {
+        let value_balance = ValueSum::from_raw((*self.value_balance()).into());
+        if display
+            .value_balance()
+            .map_or(false, |claimed| claimed != value_balance)
+            || display
+                .outputs()
+                .iter()
+                .any(|(action_idx, ..)| *action_idx >= self.actions().len())
+        {
+            return Err(Error::InvalidDisplayInfo);
+        }
+
+        let signatures = {
+            let request = SigningRequest {
+                sighash: self.sighash(),
+                spends: self.unsigned_actions().collect(),
+                display,
+            };
+            signer
+                .sign(&request)
+                .map_err(|e| Error::Signer(Box::new(e)))?
+        };
+        self.append_signatures(&signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use rand::rngs::OsRng;
+
+    use super::{DisplayInfo, OrchardSigner, SigningRequest, SoftwareSigner};
+    use crate::{
+        builder::{Builder, Error},
+        bundle::{Authorized, Bundle, Flags},
+        circuit::testing::FakeProver,
+        keys::{FullViewingKey, IncomingViewingKey, SpendAuthorizingKey, SpendingKey},
+        memo::MemoBytes,
+        note::{ExtractedNoteCommitment, Note, Nullifier},
+        primitives::redpallas::{self, SpendAuth},
+        tree::NoteCommitmentTree,
+        value::{NoteValue, ValueSum},
+    };
+
+    /// A signer that signs every spend with the wrong key.
+    struct WrongKeySigner;
+
+    impl OrchardSigner for WrongKeySigner {
+        type Error = Infallible;
+
+        fn sign(
+            &mut self,
+            request: &SigningRequest<'_>,
+        ) -> Result<Vec<redpallas::Signature<SpendAuth>>, Self::Error> {
+            let ask = SpendAuthorizingKey::from(&SpendingKey::random(&mut OsRng));
+            Ok(request
+                .spends()
+                .iter()
+                .map(|(_, parts)| {
+                    ask.randomize(&parts.alpha())
+                        .sign(OsRng, &request.sighash())
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn external_signer() {
+        let mut rng = OsRng;
+
+        let sk = SpendingKey::random(&mut rng);
+        let fvk = FullViewingKey::from(&sk);
+        let note = Note::new(
+            fvk.default_address(),
+            NoteValue::from_raw(10_000),
+            Nullifier::dummy(&mut rng),
+            &mut rng,
+        );
+        let cmx: ExtractedNoteCommitment = note.commitment().into();
+        let mut tree = NoteCommitmentTree::new(0);
+        tree.append(&cmx);
+        tree.mark();
+
+        let prepared = || {
+            let mut builder = Builder::new(Flags::from_parts(true, true), tree.root());
+            builder
+                .add_spend(fvk.clone(), note, tree.witness(&cmx).unwrap())
+                .unwrap();
+            builder
+                .add_recipient(None, fvk.default_address(), NoteValue::from_raw(7000), None)
+                .unwrap();
+            let (bundle, _): (Bundle<_, i64>, _) = builder.build(OsRng).unwrap();
+            bundle
+                .create_proof(&FakeProver)
+                .unwrap()
+                .prepare(OsRng, [7; 32])
+        };
+
+        // The display information can be taken from the bundle itself.
+        let bundle = prepared();
+        let display = bundle
+            .decrypt_outputs_with_keys(&[IncomingViewingKey::from(&fvk)])
+            .into_iter()
+            .fold(
+                DisplayInfo::new().with_value_balance(ValueSum::from_raw(*bundle.value_balance())),
+                |display, (action_idx, _, note, address, memo)| {
+                    display.with_output(action_idx, address, note.value(), memo)
+                },
+            );
+        assert_eq!(display.outputs().len(), 1);
+
+        let mut signer = SoftwareSigner::new(vec![SpendAuthorizingKey::from(&sk)], OsRng);
+        let bundle: Bundle<Authorized, i64> = bundle
+            .sign_with(&mut signer, display)
+            .unwrap()
+            .finalize()
+            .unwrap();
+        for action in bundle.actions() {
+            assert!(action.rk().verify(&[7; 32], action.authorization()).is_ok());
+        }
+
+        // A signer without the right key leaves the spend unsigned.
+        let mut signer = SoftwareSigner::new(
+            vec![SpendAuthorizingKey::from(&SpendingKey::random(&mut rng))],
+            OsRng,
+        );
+        let bundle = prepared()
+            .sign_with(&mut signer, DisplayInfo::new())
+            .unwrap();
+        assert!(matches!(bundle.finalize(), Err(Error::MissingSignatures)));
+
+        // Display information that doesn't match the bundle is rejected.
+        assert!(matches!(
+            prepared().sign_with(
+                &mut signer,
+                DisplayInfo::new().with_value_balance(ValueSum::from_raw(1))
+            ),
+            Err(Error::InvalidDisplayInfo)
+        ));
+        let bundle = prepared();
+        let n_actions = bundle.actions().len();
+        assert!(matches!(
+            bundle.sign_with(
+                &mut signer,
+                DisplayInfo::new().with_output(
+                    n_actions,
+                    fvk.default_address(),
+                    NoteValue::from_raw(7000),
+                    MemoBytes::empty()
+                )
+            ),
+            Err(Error::InvalidDisplayInfo)
+        ));
+
+        // Invalid signatures are rejected.
+        assert!(matches!(
+            prepared().sign_with(&mut WrongKeySigner, DisplayInfo::new()),
+            Err(Error::InvalidExternalSignature)
+        ));
+    }
+}
@@ -1,20 +1,33 @@
 //! Structs related to bundles of Orchard actions.
 
+mod batch;
 pub mod commitments;
 
-use std::io;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, Read, Write};
 use std::mem;
 
 use blake2b_simd::Hash as Blake2bHash;
 use nonempty::NonEmpty;
+use zcash_note_encryption::{batch as note_batch, try_output_recovery_with_ovk};
+
+pub use batch::BatchValidator;
 
 use crate::{
     bundle::commitments::{hash_bundle_auth_data, hash_bundle_txid_data},
     circuit::{Instance, Proof, VerifyingKey},
-    note::{ExtractedNoteCommitment, Nullifier, TransmittedNoteCiphertext},
+    keys::{IncomingViewingKey, OutgoingViewingKey},
+    memo::MemoBytes,
+    note::{ExtractedNoteCommitment, Note, Nullifier, TransmittedNoteCiphertext},
+    note_encryption::{decrypt_with_first_key, OrchardDomain},
     primitives::redpallas::{self, Binding, SpendAuth},
+    serialization::{
+        invalid_data, read_bytes, read_compact_size_bounded, write_compact_size, MAX_COMPACT_SIZE,
+    },
     tree::Anchor,
     value::{ValueCommitTrapdoor, ValueCommitment, ValueSum},
+    Address,
 };
 
 /// An action applied to the global ledger.
@@ -284,6 +297,12 @@ impl<T: Authorization, V> Bundle<T, V> {
 
     /// Computes a commitment to the effects of this bundle, suitable for inclusion within
     /// a transaction ID.
+    ///
+    /// This is also the Orchard bundle's contribution to the transaction sighash defined
+    /// in [ZIP 244], and does not depend on the proof or signatures. It can therefore be
+    /// computed for an in-progress bundle before its proof has been created.
+    ///
+    /// [ZIP 244]: https://zips.z.cash/zip-0244
     pub fn commitment<'a>(&'a self) -> BundleCommitment
     where
         i64: From<&'a V>,
@@ -354,6 +373,63 @@ impl<T: Authorization, V> Bundle<T, V> {
             .map(|a| a.to_instance(self.flags, self.anchor))
             .collect()
     }
+
+    /// Performs trial decryption of each action in the bundle with each of the given
+    /// incoming viewing keys.
+    ///
+    /// Returns a `(action index, key index, note, recipient, memo)` tuple for each action
+    /// that could be decrypted, in order of action index. If an action can be decrypted
+    /// with more than one of the keys, the first such key is used.
+    pub fn decrypt_outputs_with_keys(
+        &self,
+        keys: &[IncomingViewingKey],
+    ) -> Vec<(usize, usize, Note, Address, MemoBytes)> {
+        let outputs: Vec<_> = self
+            .actions
+            .iter()
+            .map(|action| (OrchardDomain::for_action(action), action))
+            .collect();
+
+        decrypt_with_first_key(outputs.len(), keys, |ivk| {
+            // Trial-decrypt every action with this key at once, so that the key
+            // agreement results are batched through `OrchardDomain::batch_kdf`.
+            note_batch::try_note_decryption(std::slice::from_ref(ivk), &outputs)
+        })
+        .into_iter()
+        .map(|(action_idx, key_idx, (note, recipient, memo))| {
+            (action_idx, key_idx, note, recipient, memo)
+        })
+        .collect()
+    }
+
+    /// Attempts to recover the output notes of each action in the bundle with each of
+    /// the given outgoing viewing keys.
+    ///
+    /// Returns a `(action index, key index, note, recipient, memo)` tuple for each action
+    /// that could be recovered, in order of action index. If an action can be recovered
+    /// with more than one of the keys, the first such key is used.
+    pub fn recover_outputs_with_ovks(
+        &self,
+        keys: &[OutgoingViewingKey],
+    ) -> Vec<(usize, usize, Note, Address, MemoBytes)> {
+        self.actions
+            .iter()
+            .enumerate()
+            .filter_map(|(action_idx, action)| {
+                let domain = OrchardDomain::for_action(action);
+                keys.iter().enumerate().find_map(|(key_idx, ovk)| {
+                    try_output_recovery_with_ovk(
+                        &domain,
+                        ovk,
+                        action,
+                        action.cv_net(),
+                        &action.encrypted_note().out_ciphertext,
+                    )
+                    .map(|(note, recipient, memo)| (action_idx, key_idx, note, recipient, memo))
+                })
+            })
+            .collect()
+    }
 }
 
 impl<T: Authorization, V: Copy + Into<ValueSum>> Bundle<T, V> {
@@ -372,6 +448,41 @@ impl<T: Authorization, V: Copy + Into<ValueSum>> Bundle<T, V> {
     }
 }
 
+/// The check that failed when verifying a bundle with [`Bundle::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The public inputs of the action at the given index cannot be given to the
+    /// circuit, for example because its `rk` is the identity.
+    MalformedInstance(usize),
+    /// The spend authorization signature of the action at the given index is invalid.
+    SpendAuthSignature(usize),
+    /// The binding signature is invalid.
+    BindingSignature,
+    /// The proof is invalid.
+    Proof,
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::MalformedInstance(index) => {
+                write!(f, "Orchard action {} has malformed public inputs", index)
+            }
+            VerificationError::SpendAuthSignature(index) => write!(
+                f,
+                "Orchard action {} has an invalid spend authorization signature",
+                index
+            ),
+            VerificationError::BindingSignature => {
+                write!(f, "Orchard binding signature is invalid")
+            }
+            VerificationError::Proof => write!(f, "Orchard proof is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
 /// Authorizing data for a bundle of actions, ready to be committed to the ledger.
 #[derive(Debug, Clone)]
 pub struct Authorized {
@@ -424,6 +535,180 @@ impl<V> Bundle<Authorized, V> {
             .proof()
             .verify(vk, &self.to_instances())
     }
+
+    /// Writes this bundle in the format used within a v5 transaction, as defined in
+    /// [Zcash Protocol Spec § 7.1: Transaction Encoding and Consensus][txnencoding].
+    ///
+    /// A transaction without an Orchard bundle encodes it as the single byte `0x00`,
+    /// which [`Bundle::read`] parses as `None`.
+    ///
+    /// [txnencoding]: https://zips.z.cash/protocol/nu5.pdf#txnencoding
+    pub fn write<'a, W: Write>(&'a self, mut writer: W) -> io::Result<()>
+    where
+        i64: From<&'a V>,
+    {
+        write_compact_size(&mut writer, self.actions.len() as u64)?;
+        for action in self.actions.iter() {
+            write_action_without_auth(action, &mut writer)?;
+        }
+
+        writer.write_all(&[self.flags.to_byte()])?;
+        writer.write_all(&i64::from(self.value_balance()).to_le_bytes())?;
+        writer.write_all(&self.anchor.to_bytes())?;
+
+        let proof = self.authorization.proof().as_ref();
+        write_compact_size(&mut writer, proof.len() as u64)?;
+        writer.write_all(proof)?;
+
+        for action in self.actions.iter() {
+            writer.write_all(&<[u8; 64]>::from(action.authorization()))?;
+        }
+        writer.write_all(&<[u8; 64]>::from(self.authorization.binding_signature()))
+    }
+}
+
+/// The maximum number of actions in a bundle.
+///
+/// Defined in [ZIP 225: Orchard Action Description][actiondescription].
+///
+/// [actiondescription]: https://zips.z.cash/zip-0225#orchard-action-description-orchardaction
+pub(crate) const MAX_ACTIONS: usize = (1 << 16) - 1;
+
+/// Returns the size in bytes of the proof for a bundle with `n_actions` actions.
+///
+/// Defined in [ZIP 225: Transaction Format][txformat].
+///
+/// [txformat]: https://zips.z.cash/zip-0225#transaction-format
+pub(crate) fn proof_len(n_actions: usize) -> usize {
+    2720 + 2272 * n_actions
+}
+
+impl<V: Copy + Into<ValueSum>> Bundle<Authorized, V> {
+    /// Verifies the proof and signatures of this bundle, where `sighash` is the
+    /// transaction sighash that the signatures are expected to sign.
+    ///
+    /// The checks are made in order of increasing cost, and the first failing check is
+    /// returned. Use [`BatchValidator`] to verify many bundles efficiently.
+    pub fn verify(&self, vk: &VerifyingKey, sighash: &[u8; 32]) -> Result<(), VerificationError> {
+        let instances = self.to_instances();
+        if let Some(index) = instances.iter().position(|i| !i.is_well_formed()) {
+            return Err(VerificationError::MalformedInstance(index));
+        }
+
+        for (index, action) in self.actions.iter().enumerate() {
+            action
+                .rk()
+                .verify(sighash, action.authorization())
+                .map_err(|_| VerificationError::SpendAuthSignature(index))?;
+        }
+
+        self.binding_validating_key()
+            .verify(sighash, &self.authorization.binding_signature)
+            .map_err(|_| VerificationError::BindingSignature)?;
+
+        self.authorization
+            .proof
+            .verify(vk, &instances)
+            .map_err(|_| VerificationError::Proof)
+    }
+}
+
+impl<V: TryFrom<i64>> Bundle<Authorized, V> {
+    /// Reads a bundle in the format used within a v5 transaction, as defined in
+    /// [Zcash Protocol Spec § 7.1: Transaction Encoding and Consensus][txnencoding].
+    ///
+    /// Returns `Ok(None)` if the encoding contains no actions. Returns an error if any
+    /// nullifier, note commitment, value commitment, randomized verification key or
+    /// anchor is not canonically encoded, if the flags have unknown bits set, if the
+    /// value balance cannot be represented as a `V`, or if the proof does not have the
+    /// size required for the number of actions.
+    ///
+    /// [txnencoding]: https://zips.z.cash/protocol/nu5.pdf#txnencoding
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Option<Self>> {
+        let n_actions = read_compact_size_bounded(&mut reader, MAX_ACTIONS)?;
+        if n_actions == 0 {
+            return Ok(None);
+        }
+
+        // We don't preallocate based on the untrusted action count.
+        let mut actions = vec![];
+        for _ in 0..n_actions {
+            actions.push(read_action_without_auth(&mut reader)?);
+        }
+
+        let flags = Flags::from_byte(read_bytes::<_, 1>(&mut reader)?[0])?;
+        let value_balance = V::try_from(i64::from_le_bytes(read_bytes(&mut reader)?))
+            .map_err(|_| invalid_data("value balance out of range"))?;
+        let anchor = Anchor::from_bytes(read_bytes(&mut reader)?)
+            .ok_or_else(|| invalid_data("non-canonical anchor"))?;
+
+        let expected_proof_len = proof_len(n_actions);
+        if read_compact_size_bounded(&mut reader, MAX_COMPACT_SIZE)? != expected_proof_len {
+            return Err(invalid_data(
+                "proof size does not match the number of actions",
+            ));
+        }
+        let mut proof = vec![];
+        (&mut reader)
+            .take(expected_proof_len as u64)
+            .read_to_end(&mut proof)?;
+        if proof.len() != expected_proof_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "proof is truncated",
+            ));
+        }
+
+        let mut authorized_actions = Vec::with_capacity(n_actions);
+        for action in actions {
+            let sig = redpallas::Signature::from(read_bytes::<_, 64>(&mut reader)?);
+            authorized_actions.push(action.map(|()| sig));
+        }
+        let binding_signature = redpallas::Signature::from(read_bytes::<_, 64>(&mut reader)?);
+
+        Ok(Some(Bundle::from_parts(
+            NonEmpty::from_vec(authorized_actions).expect("n_actions > 0"),
+            flags,
+            value_balance,
+            anchor,
+            Authorized::from_parts(Proof::new(proof), binding_signature),
+        )))
+    }
+}
+
+/// Writes the fields of an action that precede its spend authorization signature.
+pub(crate) fn write_action_without_auth<T, W: Write>(
+    action: &Action<T>,
+    mut writer: W,
+) -> io::Result<()> {
+    writer.write_all(&action.cv_net().to_bytes())?;
+    writer.write_all(&action.nullifier().to_bytes())?;
+    writer.write_all(&<[u8; 32]>::from(action.rk()))?;
+    writer.write_all(&action.cmx().to_bytes())?;
+    writer.write_all(&action.encrypted_note().epk_bytes)?;
+    writer.write_all(&action.encrypted_note().enc_ciphertext)?;
+    writer.write_all(&action.encrypted_note().out_ciphertext)
+}
+
+/// Reads the fields of an action that precede its spend authorization signature.
+pub(crate) fn read_action_without_auth<R: Read>(mut reader: R) -> io::Result<Action<()>> {
+    let cv_net = Option::from(ValueCommitment::from_bytes(&read_bytes(&mut reader)?))
+        .ok_or_else(|| invalid_data("invalid value commitment"))?;
+    let nf = Option::from(Nullifier::from_bytes(&read_bytes(&mut reader)?))
+        .ok_or_else(|| invalid_data("non-canonical nullifier"))?;
+    let rk = redpallas::VerificationKey::try_from(read_bytes::<_, 32>(&mut reader)?)
+        .map_err(|_| invalid_data("invalid randomized verification key"))?;
+    let cmx = Option::from(ExtractedNoteCommitment::from_bytes(&read_bytes(
+        &mut reader,
+    )?))
+    .ok_or_else(|| invalid_data("non-canonical note commitment"))?;
+    let encrypted_note = TransmittedNoteCiphertext {
+        epk_bytes: read_bytes(&mut reader)?,
+        enc_ciphertext: read_bytes(&mut reader)?,
+        out_ciphertext: read_bytes(&mut reader)?,
+    };
+
+    Ok(Action::from_parts(nf, rk, cmx, encrypted_note, cv_net, ()))
 }
 
 /// A commitment to a bundle of actions.
@@ -467,7 +752,7 @@ pub mod testing {
         Anchor,
     };
 
-    use super::{Action, Authorization, Authorized, Bundle, Flags};
+    use super::{proof_len, Action, Authorization, Authorized, Bundle, Flags};
 
     /// Marker for an unauthorized bundle with no proofs or signatures.
     #[derive(Debug)]
@@ -645,7 +930,7 @@ pub mod testing {
             anchor in arb_base().prop_map(Anchor::from),
             sk in arb_binding_signing_key(),
             rng_seed in prop::array::uniform32(prop::num::u8::ANY),
-            fake_proof in vec(prop::num::u8::ANY, 1973),
+            fake_proof in vec(prop::num::u8::ANY, proof_len(n_actions)),
             fake_sighash in prop::array::uniform32(prop::num::u8::ANY),
             flags in Just(flags)
         ) -> Bundle<Authorized, ValueSum> {
@@ -665,3 +950,219 @@ pub mod testing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::io;
+
+    use nonempty::NonEmpty;
+    use proptest::prelude::*;
+    use rand::rngs::OsRng;
+
+    use super::{
+        proof_len, testing::arb_bundle, Action, Authorized, Bundle, Flags, VerificationError,
+    };
+    use crate::{
+        builder::Builder,
+        circuit::{testing::FakeProver, VerifyingKey},
+        constants::MERKLE_DEPTH_ORCHARD,
+        keys::{FullViewingKey, IncomingViewingKey, OutgoingViewingKey, SpendingKey},
+        memo::MemoBytes,
+        primitives::redpallas,
+        tree::EMPTY_ROOTS,
+        value::{NoteValue, ValueSum},
+    };
+
+    /// A value balance type for testing serialization.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Amount(i64);
+
+    impl From<i64> for Amount {
+        fn from(value: i64) -> Self {
+            Amount(value)
+        }
+    }
+
+    impl From<&Amount> for i64 {
+        fn from(amount: &Amount) -> Self {
+            amount.0
+        }
+    }
+
+    impl From<Amount> for ValueSum {
+        fn from(amount: Amount) -> Self {
+            ValueSum::from_raw(amount.0)
+        }
+    }
+
+    fn to_amount(bundle: Bundle<Authorized, ValueSum>) -> Bundle<Authorized, Amount> {
+        bundle
+            .try_map_value_balance(|v| i64::try_from(v).map(Amount))
+            .unwrap()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(20))]
+
+        #[test]
+        fn bundle_serialization(bundle in (1usize..5).prop_flat_map(arb_bundle)) {
+            let bundle = to_amount(bundle);
+
+            let mut encoded = vec![];
+            bundle.write(&mut encoded).unwrap();
+
+            let decoded = Bundle::<Authorized, Amount>::read(&encoded[..]).unwrap().unwrap();
+            assert_eq!(decoded.value_balance(), bundle.value_balance());
+            assert_eq!(decoded.anchor(), bundle.anchor());
+            assert_eq!(decoded.flags().to_byte(), bundle.flags().to_byte());
+            assert_eq!(
+                decoded.commitment().0.as_bytes(),
+                bundle.commitment().0.as_bytes(),
+            );
+            assert_eq!(
+                decoded.authorizing_commitment().0.as_bytes(),
+                bundle.authorizing_commitment().0.as_bytes(),
+            );
+
+            let mut reencoded = vec![];
+            decoded.write(&mut reencoded).unwrap();
+            assert_eq!(reencoded, encoded);
+
+            // Truncated encodings are rejected.
+            assert!(Bundle::<Authorized, Amount>::read(&encoded[..encoded.len() - 1]).is_err());
+
+            // Non-canonical nullifiers are rejected.
+            let mut invalid = encoded.clone();
+            invalid[1 + 32..1 + 64].copy_from_slice(&[0xff; 32]);
+            assert!(Bundle::<Authorized, Amount>::read(&invalid[..]).is_err());
+
+            // The proof size must match the number of actions.
+            let n_actions = bundle.actions().len();
+            let proof_len_offset = 1 + n_actions * 820 + 1 + 8 + 32;
+            assert_eq!(encoded[proof_len_offset], 0xfd);
+            let mut mismatched = encoded.clone();
+            mismatched[proof_len_offset + 1..proof_len_offset + 3]
+                .copy_from_slice(&(proof_len(n_actions + 1) as u16).to_le_bytes());
+            let err = Bundle::<Authorized, Amount>::read(&mismatched[..]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn decrypt_and_recover_outputs() {
+        let mut rng = OsRng;
+
+        let fvk = FullViewingKey::from(&SpendingKey::random(&mut rng));
+        let other_fvk = FullViewingKey::from(&SpendingKey::random(&mut rng));
+        let memo = MemoBytes::from_bytes(b"hello").unwrap();
+
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+        );
+        builder
+            .add_recipient(
+                Some(OutgoingViewingKey::from(&fvk)),
+                fvk.default_address(),
+                NoteValue::from_raw(5000),
+                Some(memo.clone()),
+            )
+            .unwrap();
+        let (bundle, _): (Bundle<_, i64>, _) = builder.build(&mut rng).unwrap();
+
+        let ivks = [
+            IncomingViewingKey::from(&other_fvk),
+            IncomingViewingKey::from(&fvk),
+        ];
+        let decrypted = bundle.decrypt_outputs_with_keys(&ivks);
+        assert_eq!(decrypted.len(), 1);
+        let (action_idx, key_idx, note, recipient, decrypted_memo) = &decrypted[0];
+        assert_eq!(*key_idx, 1);
+        assert_eq!(note.value().inner(), 5000);
+        assert_eq!(*recipient, fvk.default_address());
+        assert_eq!(decrypted_memo, &memo);
+
+        let ovks = [OutgoingViewingKey::from(&fvk)];
+        let recovered = bundle.recover_outputs_with_ovks(&ovks);
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].0, *action_idx);
+        assert_eq!(recovered[0].1, 0);
+        assert_eq!(&recovered[0].2, note);
+
+        assert!(bundle
+            .recover_outputs_with_ovks(&[OutgoingViewingKey::from(&other_fvk)])
+            .is_empty());
+    }
+
+    #[test]
+    fn verification_errors() {
+        let vk = VerifyingKey::build();
+        let mut rng = OsRng;
+        let sighash = [1; 32];
+
+        let recipient = FullViewingKey::from(&SpendingKey::random(&mut rng)).default_address();
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+        );
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(5000), None)
+            .unwrap();
+        let bundle: Bundle<Authorized, Amount> = builder
+            .build(&mut rng)
+            .unwrap()
+            .0
+            .create_proof(&FakeProver)
+            .unwrap()
+            .prepare(&mut rng, sighash)
+            .finalize()
+            .unwrap();
+
+        // Only the fake proof is invalid.
+        assert_eq!(bundle.verify(&vk, &sighash), Err(VerificationError::Proof));
+        assert_eq!(
+            bundle.verify(&vk, &[2; 32]),
+            Err(VerificationError::SpendAuthSignature(0))
+        );
+
+        let with_parts = |actions: NonEmpty<Action<_>>, value_balance: i64| {
+            Bundle::from_parts(
+                actions,
+                *bundle.flags(),
+                Amount(value_balance),
+                *bundle.anchor(),
+                bundle.authorization().clone(),
+            )
+        };
+        assert_eq!(
+            with_parts(bundle.actions().clone(), -4000).verify(&vk, &sighash),
+            Err(VerificationError::BindingSignature)
+        );
+
+        // An identity rk cannot be given to the circuit.
+        let mut actions = bundle.actions().clone();
+        let action = actions.last().clone();
+        *actions.last_mut() = Action::from_parts(
+            *action.nullifier(),
+            redpallas::VerificationKey::try_from([0; 32]).unwrap(),
+            *action.cmx(),
+            action.encrypted_note().clone(),
+            action.cv_net().clone(),
+            action.authorization().clone(),
+        );
+        assert_eq!(
+            with_parts(actions, -5000).verify(&vk, &sighash),
+            Err(VerificationError::MalformedInstance(
+                bundle.actions().len() - 1
+            ))
+        );
+    }
+
+    #[test]
+    fn empty_bundle() {
+        assert!(Bundle::<Authorized, Amount>::read(&[0x00][..])
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,213 @@
+//! Batch validation of Orchard bundles.
+
+use rand::{CryptoRng, RngCore};
+
+use super::{Authorized, Bundle};
+use crate::{
+    circuit::{BatchVerifier, Instance, Proof, VerifyingKey},
+    primitives::redpallas::{self, Binding, SpendAuth},
+    value::ValueSum,
+};
+
+/// The data from a single bundle that is checked by a [`BatchValidator`].
+#[derive(Debug)]
+struct BundleItem {
+    proof: Proof,
+    instances: Vec<Instance>,
+    signatures: Vec<redpallas::batch::Item<SpendAuth, Binding>>,
+}
+
+impl BundleItem {
+    /// Checks this bundle's proof and signatures individually.
+    fn is_valid(&self, vk: &VerifyingKey) -> bool {
+        self.signatures
+            .iter()
+            .all(|item| item.clone().verify_single().is_ok())
+            && self.proof.verify(vk, &self.instances).is_ok()
+    }
+}
+
+/// A validator for the proofs and signatures of many Orchard bundles at once.
+///
+/// Bundles are added with [`BatchValidator::add_bundle`], and then checked together
+/// with [`BatchValidator::validate`]. This is significantly faster than checking each
+/// bundle with [`Bundle::verify_proof`] and verifying its signatures separately.
+#[derive(Debug, Default)]
+pub struct BatchValidator {
+    bundles: Vec<BundleItem>,
+}
+
+impl BatchValidator {
+    /// Constructs a new, empty batch validator.
+    pub fn new() -> Self {
+        BatchValidator { bundles: vec![] }
+    }
+
+    /// Adds the proof and signatures of the given bundle to the validator.
+    ///
+    /// `sighash` is the transaction sighash that the bundle's spend authorization and
+    /// binding signatures are expected to sign.
+    pub fn add_bundle<V: Copy + Into<ValueSum>>(
+        &mut self,
+        bundle: &Bundle<Authorized, V>,
+        sighash: [u8; 32],
+    ) {
+        let signatures = bundle
+            .actions()
+            .iter()
+            .map(|action| {
+                action
+                    .rk()
+                    .create_batch_item(action.authorization().clone(), &sighash)
+            })
+            .chain(Some(bundle.binding_validating_key().create_batch_item(
+                bundle.authorization().binding_signature().clone(),
+                &sighash,
+            )))
+            .collect();
+
+        self.bundles.push(BundleItem {
+            proof: bundle.authorization().proof().clone(),
+            instances: bundle.to_instances(),
+            signatures,
+        });
+    }
+
+    /// Batch-validates the proofs and signatures of all bundles added to this validator.
+    ///
+    /// Returns `Ok(())` if every bundle is valid. If the batch fails, each bundle is
+    /// checked individually, and the indices (in the order they were added) of the
+    /// invalid bundles are returned.
+    pub fn validate<R: RngCore + CryptoRng>(
+        self,
+        vk: &VerifyingKey,
+        mut rng: R,
+    ) -> Result<(), Vec<usize>> {
+        if self.bundles.is_empty() {
+            return Ok(());
+        }
+
+        let mut signatures = redpallas::batch::Verifier::new();
+        for item in self
+            .bundles
+            .iter()
+            .flat_map(|bundle| bundle.signatures.iter())
+        {
+            signatures.queue(item.clone());
+        }
+        let signatures_valid = signatures.verify(&mut rng).is_ok();
+
+        let proofs_valid = signatures_valid && {
+            let mut proofs = BatchVerifier::new(vk);
+            self.bundles.iter().all(|bundle| {
+                proofs
+                    .add_proof(&bundle.proof, &bundle.instances, &mut rng)
+                    .is_ok()
+            }) && proofs.finalize()
+        };
+
+        if proofs_valid {
+            return Ok(());
+        }
+
+        // Fall back to checking each bundle on its own to find the invalid ones.
+        let invalid: Vec<_> = self
+            .bundles
+            .iter()
+            .enumerate()
+            .filter(|(_, bundle)| !bundle.is_valid(vk))
+            .map(|(i, _)| i)
+            .collect();
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use pasta_curves::pallas;
+    use rand::rngs::OsRng;
+
+    use super::BatchValidator;
+    use crate::{
+        builder::Builder,
+        bundle::{Authorized, Bundle, Flags},
+        circuit::{ProvingKey, VerifyingKey},
+        constants::MERKLE_DEPTH_ORCHARD,
+        keys::{FullViewingKey, SpendingKey},
+        tree::{Anchor, EMPTY_ROOTS},
+        value::{NoteValue, ValueSum},
+    };
+
+    fn shielding_bundle(pk: &ProvingKey, sighash: [u8; 32]) -> Bundle<Authorized, ValueSum> {
+        let mut rng = OsRng;
+        let sk = SpendingKey::random(&mut rng);
+        let recipient = FullViewingKey::from(&sk).default_address();
+
+        let mut builder = Builder::new(
+            Flags::from_parts(true, true),
+            EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into(),
+        );
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(5000), None)
+            .unwrap();
+        let bundle: Bundle<Authorized, i64> = builder
+            .build(&mut rng)
+            .unwrap()
+            .0
+            .create_proof(pk)
+            .unwrap()
+            .prepare(&mut rng, sighash)
+            .finalize()
+            .unwrap();
+        bundle
+            .try_map_value_balance(|v| Ok::<_, ()>(ValueSum::from_raw(v)))
+            .unwrap()
+    }
+
+    #[test]
+    fn batch_validation() {
+        let pk = ProvingKey::build();
+        let vk = VerifyingKey::build();
+
+        let bundles: Vec<_> = (0..3u8)
+            .map(|i| (shielding_bundle(&pk, [i; 32]), [i; 32]))
+            .collect();
+
+        // An empty batch is valid.
+        assert_eq!(BatchValidator::new().validate(&vk, OsRng), Ok(()));
+
+        let mut validator = BatchValidator::new();
+        for (bundle, sighash) in &bundles {
+            validator.add_bundle(bundle, *sighash);
+        }
+        assert_eq!(validator.validate(&vk, OsRng), Ok(()));
+
+        // A bundle whose signatures don't match its sighash is identified.
+        let mut validator = BatchValidator::new();
+        for (i, (bundle, sighash)) in bundles.iter().enumerate() {
+            let sighash = if i == 1 { [0xff; 32] } else { *sighash };
+            validator.add_bundle(bundle, sighash);
+        }
+        assert_eq!(validator.validate(&vk, OsRng), Err(vec![1]));
+
+        // A bundle whose proof doesn't match its instances is identified.
+        let (bundle, sighash) = &bundles[2];
+        let bad_anchor = Bundle::from_parts(
+            bundle.actions().clone(),
+            *bundle.flags(),
+            *bundle.value_balance(),
+            Anchor::from(pallas::Base::one()),
+            bundle.authorization().clone(),
+        );
+        let mut validator = BatchValidator::new();
+        validator.add_bundle(&bundles[0].0, bundles[0].1);
+        validator.add_bundle(&bad_anchor, *sighash);
+        assert_eq!(validator.validate(&vk, OsRng), Err(vec![1]));
+    }
+}
@@ -100,3 +100,30 @@ pub fn hash_bundle_auth_data<V>(bundle: &Bundle<Authorized, V>) -> Blake2bHash {
 pub fn hash_bundle_auth_empty() -> Blake2bHash {
     hasher(ZCASH_ORCHARD_SIGS_HASH_PERSONALIZATION).finalize()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_bundle_auth_empty, hash_bundle_txid_empty};
+
+    #[test]
+    fn empty_bundle_commitments() {
+        // ZIP 244 commits to an absent Orchard bundle with the BLAKE2b-256 hash of the
+        // empty string under each personalization.
+        assert_eq!(
+            hash_bundle_txid_empty().as_bytes(),
+            &[
+                0x9f, 0xbe, 0x4e, 0xd1, 0x3b, 0x0c, 0x08, 0xe6, 0x71, 0xc1, 0x1a, 0x34, 0x07, 0xd8,
+                0x4e, 0x11, 0x17, 0xcd, 0x45, 0x02, 0x8a, 0x2e, 0xee, 0x1b, 0x9f, 0xea, 0xe7, 0x8b,
+                0x48, 0xa6, 0xe2, 0xc1,
+            ][..]
+        );
+        assert_eq!(
+            hash_bundle_auth_empty().as_bytes(),
+            &[
+                0x14, 0xed, 0xaa, 0x1e, 0x66, 0x9a, 0x63, 0xa8, 0x00, 0xbf, 0xe0, 0xb8, 0xfc, 0xd3,
+                0xd1, 0x0e, 0x36, 0x81, 0x11, 0x5b, 0xee, 0x03, 0x25, 0x3d, 0xa0, 0x2e, 0x09, 0x80,
+                0x42, 0xd9, 0xff, 0x90,
+            ][..]
+        );
+    }
+}
@@ -1,18 +1,21 @@
 //! The Orchard Action circuit implementation.
 
+use std::io;
 use std::mem;
 
+use ff::Field;
 use group::{Curve, GroupEncoding};
 use halo2::{
     circuit::{floor_planner, Layouter},
     plonk::{self, Advice, Column, Expression, Instance as InstanceColumn, Selector},
-    poly::Rotation,
-    transcript::{Blake2bRead, Blake2bWrite},
+    poly::{commitment::MSM, Rotation},
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, Transcript},
 };
 use pasta_curves::{
     arithmetic::{CurveAffine, FieldExt},
     pallas, vesta,
 };
+use rand::RngCore;
 
 use crate::{
     constants::{
@@ -31,6 +34,7 @@ use crate::{
         poseidon::{self, ConstantLength},
         redpallas::{SpendAuth, VerificationKey},
     },
+    serialization::{invalid_data, read_bytes},
     spec::NonIdentityPallasPoint,
     tree::Anchor,
     value::{NoteValue, ValueCommitTrapdoor, ValueCommitment},
@@ -56,7 +60,7 @@ use gadget::{
     utilities::{copy, CellValue, UtilitiesInstructions, Var},
 };
 
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
 use self::gadget::utilities::lookup_range_check::LookupRangeCheckConfig;
 
@@ -123,6 +127,121 @@ impl UtilitiesInstructions<pallas::Base> for Circuit {
     type Var = CellValue<pallas::Base>;
 }
 
+impl Circuit {
+    /// Writes the witnesses of this circuit.
+    ///
+    /// Each witness is encoded as a presence byte, followed by its canonical encoding if
+    /// it is present. This allows an [`OrchardProver`] to pass the witnesses to a proving
+    /// service outside of this process.
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        write_option(&mut writer, self.path.as_ref(), |path| {
+            let mut bytes = Vec::with_capacity(32 * MERKLE_DEPTH_ORCHARD);
+            for node in path.iter() {
+                bytes.extend_from_slice(&node.to_bytes());
+            }
+            bytes
+        })?;
+        write_option(&mut writer, self.pos.as_ref(), |pos| pos.to_le_bytes())?;
+        write_option(&mut writer, self.g_d_old.as_ref(), |g_d| g_d.to_bytes())?;
+        write_option(&mut writer, self.pk_d_old.as_ref(), |pk_d| pk_d.to_bytes())?;
+        write_option(&mut writer, self.v_old.as_ref(), |v| v.to_bytes())?;
+        write_option(&mut writer, self.rho_old.as_ref(), |rho| rho.to_bytes())?;
+        write_option(&mut writer, self.psi_old.as_ref(), |psi| psi.to_bytes())?;
+        write_option(&mut writer, self.rcm_old.as_ref(), |rcm| rcm.to_bytes())?;
+        write_option(&mut writer, self.cm_old.as_ref(), |cm| cm.to_bytes())?;
+        write_option(&mut writer, self.alpha.as_ref(), |alpha| alpha.to_bytes())?;
+        write_option(&mut writer, self.ak.as_ref(), |ak| ak.to_bytes())?;
+        write_option(&mut writer, self.nk.as_ref(), |nk| nk.to_bytes())?;
+        write_option(&mut writer, self.rivk.as_ref(), |rivk| rivk.to_bytes())?;
+        write_option(&mut writer, self.g_d_new_star.as_ref(), |g_d| *g_d)?;
+        write_option(&mut writer, self.pk_d_new_star.as_ref(), |pk_d| *pk_d)?;
+        write_option(&mut writer, self.v_new.as_ref(), |v| v.to_bytes())?;
+        write_option(&mut writer, self.psi_new.as_ref(), |psi| psi.to_bytes())?;
+        write_option(&mut writer, self.rcm_new.as_ref(), |rcm| rcm.to_bytes())?;
+        write_option(&mut writer, self.rcv.as_ref(), |rcv| rcv.to_bytes())
+    }
+
+    /// Reads the witnesses of a circuit written by [`Circuit::write`].
+    ///
+    /// Returns an error if any witness is not canonically encoded.
+    pub fn read<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        Ok(Circuit {
+            path: read_option(&mut reader, |bytes: [u8; 32 * MERKLE_DEPTH_ORCHARD]| {
+                let mut path = [pallas::Base::zero(); MERKLE_DEPTH_ORCHARD];
+                for (node, chunk) in path.iter_mut().zip(bytes.chunks_exact(32)) {
+                    *node = Option::from(pallas::Base::from_bytes(chunk.try_into().unwrap()))?;
+                }
+                Some(path)
+            })?,
+            pos: read_option(&mut reader, |bytes| Some(u32::from_le_bytes(bytes)))?,
+            g_d_old: read_option(&mut reader, |bytes| {
+                NonIdentityPallasPoint::from_bytes(&bytes).into()
+            })?,
+            pk_d_old: read_option(&mut reader, |bytes| {
+                DiversifiedTransmissionKey::from_bytes(&bytes).into()
+            })?,
+            v_old: read_option(&mut reader, |bytes| Some(NoteValue::from_bytes(bytes)))?,
+            rho_old: read_option(&mut reader, |bytes| Nullifier::from_bytes(&bytes).into())?,
+            psi_old: read_option(&mut reader, |bytes| pallas::Base::from_bytes(&bytes).into())?,
+            rcm_old: read_option(&mut reader, |bytes| {
+                NoteCommitTrapdoor::from_bytes(&bytes).into()
+            })?,
+            cm_old: read_option(&mut reader, |bytes| {
+                NoteCommitment::from_bytes(&bytes).into()
+            })?,
+            alpha: read_option(&mut reader, |bytes| {
+                pallas::Scalar::from_bytes(&bytes).into()
+            })?,
+            ak: read_option(&mut reader, |bytes| SpendValidatingKey::from_bytes(&bytes))?,
+            nk: read_option(&mut reader, |bytes| {
+                NullifierDerivingKey::from_bytes(&bytes).into()
+            })?,
+            rivk: read_option(&mut reader, |bytes| {
+                CommitIvkRandomness::from_bytes(&bytes).into()
+            })?,
+            g_d_new_star: read_option(&mut reader, Some)?,
+            pk_d_new_star: read_option(&mut reader, Some)?,
+            v_new: read_option(&mut reader, |bytes| Some(NoteValue::from_bytes(bytes)))?,
+            psi_new: read_option(&mut reader, |bytes| pallas::Base::from_bytes(&bytes).into())?,
+            rcm_new: read_option(&mut reader, |bytes| {
+                NoteCommitTrapdoor::from_bytes(&bytes).into()
+            })?,
+            rcv: read_option(&mut reader, |bytes| {
+                ValueCommitTrapdoor::from_bytes(&bytes).into()
+            })?,
+        })
+    }
+}
+
+/// Writes an optional value as a presence byte, followed by its encoding if present.
+fn write_option<W: io::Write, T, B: AsRef<[u8]>>(
+    mut writer: W,
+    value: Option<&T>,
+    to_bytes: impl FnOnce(&T) -> B,
+) -> io::Result<()> {
+    match value {
+        None => writer.write_all(&[0]),
+        Some(value) => {
+            writer.write_all(&[1])?;
+            writer.write_all(to_bytes(value).as_ref())
+        }
+    }
+}
+
+/// Reads an optional value written by [`write_option`].
+fn read_option<R: io::Read, T, const N: usize>(
+    mut reader: R,
+    from_bytes: impl FnOnce([u8; N]) -> Option<T>,
+) -> io::Result<Option<T>> {
+    match read_bytes::<_, 1>(&mut reader)?[0] {
+        0 => Ok(None),
+        1 => from_bytes(read_bytes(&mut reader)?)
+            .map(Some)
+            .ok_or_else(|| invalid_data("invalid circuit witness")),
+        _ => Err(invalid_data("invalid presence flag for circuit witness")),
+    }
+}
+
 impl plonk::Circuit<pallas::Base> for Circuit {
     type Config = Config;
     type FloorPlanner = floor_planner::V1;
@@ -778,6 +897,69 @@ impl VerifyingKey {
 
         VerifyingKey { params, vk }
     }
+
+    /// Writes this verifying key to a versioned key file.
+    ///
+    /// The file can be read back with either [`VerifyingKey::read`] or
+    /// [`ProvingKey::read`].
+    pub fn write<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        write_key_file(&self.params, writer)
+    }
+
+    /// Reads a verifying key from a key file written by [`VerifyingKey::write`] or
+    /// [`ProvingKey::write`].
+    ///
+    /// Returns an error if the file has an unknown version, or if the verifying key
+    /// derived from its parameters is not the Orchard circuit's verifying key.
+    ///
+    /// Only the parameters are stored in the file, so the verifying key is still
+    /// generated from them with `keygen_vk`.
+    pub fn read<R: io::Read>(reader: R) -> io::Result<Self> {
+        let (params, vk) = read_key_file(reader)?;
+        Ok(VerifyingKey { params, vk })
+    }
+}
+
+/// A verifier that checks multiple Orchard proofs in a batch.
+#[derive(Debug)]
+pub(crate) struct BatchVerifier<'a> {
+    vk: &'a VerifyingKey,
+    msm: MSM<'a, vesta::Affine>,
+}
+
+impl<'a> BatchVerifier<'a> {
+    /// Constructs a new batch verifier for proofs under the given verifying key.
+    pub(crate) fn new(vk: &'a VerifyingKey) -> Self {
+        BatchVerifier {
+            vk,
+            msm: vk.params.empty_msm(),
+        }
+    }
+
+    /// Adds a proof and its instances to the batch.
+    ///
+    /// Returns an error if the proof is malformed. A proof that is added successfully
+    /// is only checked for validity by [`BatchVerifier::finalize`].
+    pub(crate) fn add_proof<R: RngCore>(
+        &mut self,
+        proof: &Proof,
+        instances: &[Instance],
+        rng: R,
+    ) -> Result<(), plonk::Error> {
+        let mut msm = proof.verification_msm(self.vk, instances)?;
+        // Scale the proof's MSM by a random factor, so that an invalid proof cannot
+        // cancel out with the rest of the batch (except with negligible probability).
+        msm.scale(vesta::Scalar::random(rng));
+        self.msm.add_msm(&msm);
+        Ok(())
+    }
+
+    /// Finalizes the batch and checks its validity.
+    ///
+    /// Returns `false` if *some* proof in the batch was invalid.
+    pub(crate) fn finalize(self) -> bool {
+        self.msm.eval()
+    }
 }
 
 /// The proving key for the Orchard Action circuit.
@@ -798,6 +980,127 @@ impl ProvingKey {
 
         ProvingKey { params, pk }
     }
+
+    /// Writes this proving key to a versioned key file.
+    ///
+    /// The file can be read back with either [`ProvingKey::read`] or
+    /// [`VerifyingKey::read`].
+    pub fn write<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        write_key_file(&self.params, writer)
+    }
+
+    /// Reads a proving key from a key file written by [`ProvingKey::write`] or
+    /// [`VerifyingKey::write`].
+    ///
+    /// Returns an error if the file has an unknown version, or if the verifying key
+    /// derived from its parameters is not the Orchard circuit's verifying key.
+    ///
+    /// `halo2` cannot serialize circuit keys, so only the parameters are stored in the
+    /// file. Both the verifying key and the proving key are still generated from them,
+    /// with `keygen_vk` and `keygen_pk` respectively; reading a key file only saves the
+    /// cost of generating the parameters.
+    pub fn read<R: io::Read>(reader: R) -> io::Result<Self> {
+        let (params, vk) = read_key_file(reader)?;
+        let circuit: Circuit = Default::default();
+        let pk = plonk::keygen_pk(&params, vk, &circuit)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))?;
+
+        Ok(ProvingKey { params, pk })
+    }
+}
+
+/// A source of proofs for the Orchard Action circuit.
+///
+/// [`ProvingKey`] implements this trait by creating proofs locally with
+/// [`Proof::create`]. Other implementations might delegate proof creation to a remote
+/// service, or return previously-created proofs from a cache.
+pub trait OrchardProver {
+    /// Creates a proof for the given circuits and instances.
+    fn create_proof(
+        &self,
+        circuits: &[Circuit],
+        instances: &[Instance],
+    ) -> Result<Proof, plonk::Error>;
+}
+
+impl OrchardProver for ProvingKey {
+    fn create_proof(
+        &self,
+        circuits: &[Circuit],
+        instances: &[Instance],
+    ) -> Result<Proof, plonk::Error> {
+        Proof::create(self, circuits, instances)
+    }
+}
+
+/// The magic bytes at the start of a key file.
+const KEY_FILE_MAGIC: [u8; 8] = *b"ORCHKEYS";
+
+/// The current version of the key file format.
+const KEY_FILE_VERSION: u8 = 2;
+
+/// The digest of the Orchard circuit's verifying key, as computed by [`vk_digest`].
+///
+/// A key file is only accepted if the verifying key generated from its parameters has
+/// this digest, so that a key file cannot substitute parameters with a known trapdoor.
+/// This must be updated whenever the circuit changes, and is checked against the circuit
+/// by the `pinned_vk_digest` test.
+// TODO: Set this to the digest reported by the `pinned_vk_digest` test. Until then, every
+// key file is rejected.
+const ORCHARD_VK_DIGEST: [u8; 32] = [0; 32];
+
+/// Returns a digest of the given verifying key, which commits to the circuit's
+/// constraint system and fixed columns.
+fn vk_digest(vk: &plonk::VerifyingKey<vesta::Affine>) -> io::Result<[u8; 32]> {
+    let mut transcript = Blake2bWrite::<_, vesta::Affine, Challenge255<_>>::init(vec![]);
+    vk.hash_into(&mut transcript)?;
+    Ok(transcript.squeeze_challenge().get_scalar().to_bytes())
+}
+
+/// Writes a key file.
+///
+/// Key file format:
+/// - 8 bytes: [`KEY_FILE_MAGIC`]
+/// - 1 byte: [`KEY_FILE_VERSION`]
+/// - The serialized polynomial commitment parameters.
+///
+/// `halo2` does not support serializing the circuit keys themselves, so they are
+/// regenerated from the cached parameters when the file is read.
+fn write_key_file<W: io::Write>(
+    params: &halo2::poly::commitment::Params<vesta::Affine>,
+    mut writer: W,
+) -> io::Result<()> {
+    writer.write_all(&KEY_FILE_MAGIC)?;
+    writer.write_all(&[KEY_FILE_VERSION])?;
+    params.write(&mut writer)
+}
+
+/// Reads a key file, and checks that the verifying key generated from its parameters
+/// is the Orchard circuit's verifying key.
+fn read_key_file<R: io::Read>(
+    mut reader: R,
+) -> io::Result<(
+    halo2::poly::commitment::Params<vesta::Affine>,
+    plonk::VerifyingKey<vesta::Affine>,
+)> {
+    if read_bytes::<_, 8>(&mut reader)? != KEY_FILE_MAGIC {
+        return Err(invalid_data("not an Orchard key file"));
+    }
+    if read_bytes::<_, 1>(&mut reader)? != [KEY_FILE_VERSION] {
+        return Err(invalid_data("unsupported key file version"));
+    }
+
+    let params = halo2::poly::commitment::Params::<vesta::Affine>::read(&mut reader)?;
+    let circuit: Circuit = Default::default();
+    let vk = plonk::keygen_vk(&params, &circuit)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))?;
+    if vk_digest(&vk)? != ORCHARD_VK_DIGEST {
+        return Err(invalid_data(
+            "key file does not match the Orchard circuit's verifying key",
+        ));
+    }
+
+    Ok((params, vk))
 }
 
 /// Public inputs to the Orchard Action circuit.
@@ -813,7 +1116,62 @@ pub struct Instance {
 }
 
 impl Instance {
-    fn to_halo2_instance(&self) -> [[vesta::Scalar; 9]; 1] {
+    /// Writes these public inputs.
+    ///
+    /// Each public input is written in its canonical encoding, in the order of the
+    /// fields of the Action description, followed by a byte containing the enable flags
+    /// in the same format as [`Flags`].
+    ///
+    /// [`Flags`]: crate::bundle::Flags
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.anchor.to_bytes())?;
+        writer.write_all(&self.cv_net.to_bytes())?;
+        writer.write_all(&self.nf_old.to_bytes())?;
+        writer.write_all(&<[u8; 32]>::from(&self.rk))?;
+        writer.write_all(&self.cmx.to_bytes())?;
+        writer.write_all(&[u8::from(self.enable_spend) | (u8::from(self.enable_output) << 1)])
+    }
+
+    /// Reads public inputs written by [`Instance::write`].
+    ///
+    /// Returns an error if any public input is not canonically encoded, or if the enable
+    /// flags have unknown bits set.
+    pub fn read<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let anchor = Anchor::from_bytes(read_bytes(&mut reader)?)
+            .ok_or_else(|| invalid_data("non-canonical anchor"))?;
+        let cv_net = Option::from(ValueCommitment::from_bytes(&read_bytes(&mut reader)?))
+            .ok_or_else(|| invalid_data("invalid value commitment"))?;
+        let nf_old = Option::from(Nullifier::from_bytes(&read_bytes(&mut reader)?))
+            .ok_or_else(|| invalid_data("non-canonical nullifier"))?;
+        let rk = VerificationKey::try_from(read_bytes::<_, 32>(&mut reader)?)
+            .map_err(|_| invalid_data("invalid randomized verification key"))?;
+        let cmx = Option::from(ExtractedNoteCommitment::from_bytes(&read_bytes(
+            &mut reader,
+        )?))
+        .ok_or_else(|| invalid_data("non-canonical note commitment"))?;
+        let flags = read_bytes::<_, 1>(&mut reader)?[0];
+        if flags & !0b11 != 0 {
+            return Err(invalid_data("unknown bits set in instance flags"));
+        }
+
+        Ok(Instance {
+            anchor,
+            cv_net,
+            nf_old,
+            rk,
+            cmx,
+            enable_spend: flags & 0b01 != 0,
+            enable_output: flags & 0b10 != 0,
+        })
+    }
+
+    /// Returns `false` if these public inputs cannot be given to the circuit, because
+    /// `rk` is the identity.
+    pub(crate) fn is_well_formed(&self) -> bool {
+        self.to_halo2_instance().is_some()
+    }
+
+    fn to_halo2_instance(&self) -> Option<[[vesta::Scalar; 9]; 1]> {
         let mut instance = [vesta::Scalar::zero(); 9];
 
         instance[ANCHOR] = self.anchor.inner();
@@ -821,11 +1179,12 @@ impl Instance {
         instance[CV_NET_Y] = self.cv_net.y();
         instance[NF_OLD] = self.nf_old.0;
 
-        let rk = pallas::Point::from_bytes(&self.rk.clone().into())
-            .unwrap()
-            .to_affine()
-            .coordinates()
-            .unwrap();
+        let rk =
+            pallas::Affine::from_bytes(&self.rk.clone().into()).and_then(|rk| rk.coordinates());
+        if rk.is_none().into() {
+            return None;
+        }
+        let rk = rk.unwrap();
 
         instance[RK_X] = *rk.x();
         instance[RK_Y] = *rk.y();
@@ -833,7 +1192,7 @@ impl Instance {
         instance[ENABLE_SPEND] = vesta::Scalar::from_u64(self.enable_spend.into());
         instance[ENABLE_OUTPUT] = vesta::Scalar::from_u64(self.enable_output.into());
 
-        [instance]
+        Some([instance])
     }
 }
 
@@ -861,7 +1220,11 @@ impl Proof {
         circuits: &[Circuit],
         instances: &[Instance],
     ) -> Result<Self, plonk::Error> {
-        let instances: Vec<_> = instances.iter().map(|i| i.to_halo2_instance()).collect();
+        let instances: Vec<_> = instances
+            .iter()
+            .map(|i| i.to_halo2_instance())
+            .collect::<Option<_>>()
+            .ok_or(plonk::Error::SynthesisError)?;
         let instances: Vec<Vec<_>> = instances
             .iter()
             .map(|i| i.iter().map(|c| &c[..]).collect())
@@ -875,7 +1238,25 @@ impl Proof {
 
     /// Verifies this proof with the given instances.
     pub fn verify(&self, vk: &VerifyingKey, instances: &[Instance]) -> Result<(), plonk::Error> {
-        let instances: Vec<_> = instances.iter().map(|i| i.to_halo2_instance()).collect();
+        if self.verification_msm(vk, instances)?.eval() {
+            Ok(())
+        } else {
+            Err(plonk::Error::ConstraintSystemFailure)
+        }
+    }
+
+    /// Checks the structure of this proof with the given instances, and returns the
+    /// multiscalar multiplication that must evaluate to zero for the proof to be valid.
+    fn verification_msm<'a>(
+        &self,
+        vk: &'a VerifyingKey,
+        instances: &[Instance],
+    ) -> Result<MSM<'a, vesta::Affine>, plonk::Error> {
+        let instances: Vec<_> = instances
+            .iter()
+            .map(|i| i.to_halo2_instance())
+            .collect::<Option<_>>()
+            .ok_or(plonk::Error::ConstraintSystemFailure)?;
         let instances: Vec<Vec<_>> = instances
             .iter()
             .map(|i| i.iter().map(|c| &c[..]).collect())
@@ -885,12 +1266,7 @@ impl Proof {
         let msm = vk.params.empty_msm();
         let mut transcript = Blake2bRead::init(&self.0[..]);
         let guard = plonk::verify_proof(&vk.params, &vk.vk, msm, &instances, &mut transcript)?;
-        let msm = guard.clone().use_challenges();
-        if msm.eval() {
-            Ok(())
-        } else {
-            Err(plonk::Error::ConstraintSystemFailure)
-        }
+        Ok(guard.use_challenges())
     }
 
     /// Constructs a new Proof value.
@@ -899,6 +1275,27 @@ impl Proof {
     }
 }
 
+/// Test helpers for the Orchard Action circuit.
+#[cfg(any(test, feature = "test-dependencies"))]
+pub mod testing {
+    use halo2::plonk;
+
+    use super::{Circuit, Instance, OrchardProver, Proof};
+
+    /// A prover that returns an empty proof without running the circuit.
+    ///
+    /// This makes it cheap to exercise bundle construction and signing in tests. Proofs
+    /// created by this prover will always fail verification.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct FakeProver;
+
+    impl OrchardProver for FakeProver {
+        fn create_proof(&self, _: &[Circuit], _: &[Instance]) -> Result<Proof, plonk::Error> {
+            Ok(Proof::new(vec![]))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ff::Field;
@@ -908,9 +1305,12 @@ mod tests {
     use rand::rngs::OsRng;
     use std::iter;
 
-    use super::{Circuit, Instance, Proof, ProvingKey, VerifyingKey, K};
+    use super::{
+        vk_digest, write_key_file, Circuit, Instance, Proof, ProvingKey, VerifyingKey, K,
+        KEY_FILE_VERSION, ORCHARD_VK_DIGEST,
+    };
     use crate::{
-        keys::SpendValidatingKey,
+        keys::{Scope, SpendValidatingKey},
         note::Note,
         tree::MerklePath,
         value::{ValueCommitTrapdoor, ValueCommitment},
@@ -927,7 +1327,7 @@ mod tests {
 
                 let sender_address = fvk.default_address();
                 let nk = *fvk.nk();
-                let rivk = *fvk.rivk();
+                let rivk = fvk.rivk(Scope::External);
                 let nf_old = spent_note.nullifier(&fvk);
                 let ak: SpendValidatingKey = fvk.into();
                 let alpha = pallas::Scalar::random(&mut rng);
@@ -977,6 +1377,26 @@ mod tests {
             })
             .unzip();
 
+        // The witnesses and public inputs can be passed to an external prover.
+        for (circuit, instance) in circuits.iter().zip(instances.iter()) {
+            let mut encoded = vec![];
+            circuit.write(&mut encoded).unwrap();
+            let mut reencoded = vec![];
+            Circuit::read(&encoded[..])
+                .unwrap()
+                .write(&mut reencoded)
+                .unwrap();
+            assert_eq!(reencoded, encoded);
+
+            let mut encoded = vec![];
+            instance.write(&mut encoded).unwrap();
+            let decoded = Instance::read(&encoded[..]).unwrap();
+            assert_eq!(decoded.to_halo2_instance(), instance.to_halo2_instance());
+            let mut invalid = encoded.clone();
+            *invalid.last_mut().unwrap() = 0b100;
+            assert!(Instance::read(&invalid[..]).is_err());
+        }
+
         let vk = VerifyingKey::build();
         for (circuit, instance) in circuits.iter().zip(instances.iter()) {
             assert_eq!(
@@ -985,6 +1405,7 @@ mod tests {
                     circuit,
                     instance
                         .to_halo2_instance()
+                        .unwrap()
                         .iter()
                         .map(|p| p.to_vec())
                         .collect()
@@ -998,6 +1419,48 @@ mod tests {
         let pk = ProvingKey::build();
         let proof = Proof::create(&pk, &circuits, &instances).unwrap();
         assert!(proof.verify(&vk, &instances).is_ok());
+
+        // A verifying key loaded from a proving key's key file verifies the same proofs.
+        let mut key_file = vec![];
+        pk.write(&mut key_file).unwrap();
+        let loaded_vk = VerifyingKey::read(&key_file[..]).unwrap();
+        assert!(proof.verify(&loaded_vk, &instances).is_ok());
+    }
+
+    #[test]
+    fn key_file() {
+        let vk = VerifyingKey::build();
+        let mut key_file = vec![];
+        vk.write(&mut key_file).unwrap();
+
+        let pk = ProvingKey::read(&key_file[..]).unwrap();
+        let mut reencoded = vec![];
+        pk.write(&mut reencoded).unwrap();
+        assert_eq!(reencoded, key_file);
+
+        // Unknown versions are rejected.
+        let mut bad_version = key_file.clone();
+        bad_version[8] = KEY_FILE_VERSION + 1;
+        assert!(VerifyingKey::read(&bad_version[..]).is_err());
+
+        // Parameters other than the circuit's are rejected, even though they generate a
+        // verifying key for the same circuit.
+        let other_params = halo2::poly::commitment::Params::new(K + 1);
+        let mut other_key_file = vec![];
+        write_key_file(&other_params, &mut other_key_file).unwrap();
+        assert!(VerifyingKey::read(&other_key_file[..]).is_err());
+
+        // Truncated files are rejected.
+        assert!(VerifyingKey::read(&key_file[..key_file.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn pinned_vk_digest() {
+        let vk = VerifyingKey::build();
+        assert_eq!(
+            hex::encode(vk_digest(&vk.vk).unwrap()),
+            hex::encode(ORCHARD_VK_DIGEST)
+        );
     }
 
     #[cfg(feature = "dev-graph")]
@@ -0,0 +1,272 @@
+//! Consensus rules for Orchard bundles that are not enforced by the proof or signatures.
+//!
+//! A node must check these rules in addition to verifying the bundle's proof, spend
+//! authorization signatures and binding signature. Rules that involve state outside of
+//! the bundle (such as nullifiers spent in earlier transactions) remain the caller's
+//! responsibility, except for the anchor, which is checked through a callback.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    bundle::{Authorization, Bundle},
+    keys::EphemeralPublicKey,
+    tree::Anchor,
+};
+
+/// The maximum amount of money, in zatoshis, that can exist.
+///
+/// Defined in [Zcash Protocol Spec § 5.3: Constants][constants].
+///
+/// [constants]: https://zips.z.cash/protocol/nu5.pdf#constants
+pub const MAX_MONEY: i64 = 21_000_000 * 100_000_000;
+
+/// A consensus rule that a bundle violates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Both spends and outputs are disabled, so the bundle must not contain any actions.
+    SpendsAndOutputsDisabled,
+    /// Spends are disabled, but the bundle's value balance is positive. Without spends,
+    /// the bundle can only move value into the shielded pool.
+    ValueBalanceRequiresSpends,
+    /// Outputs are disabled, but the bundle's value balance is negative. Without
+    /// outputs, the bundle can only move value out of the shielded pool.
+    ValueBalanceRequiresOutputs,
+    /// The bundle's value balance is outside the range `-MAX_MONEY..=MAX_MONEY`.
+    ValueBalanceOutOfRange(i64),
+    /// The bundle's anchor is not the root of a known note commitment tree.
+    UnknownAnchor,
+    /// The action at `index` reveals the same nullifier as the earlier action at `first`.
+    DuplicateNullifier {
+        /// The index of the action that repeats the nullifier.
+        index: usize,
+        /// The index of the first action that reveals the nullifier.
+        first: usize,
+    },
+    /// The ephemeral key of the action at the given index is not a canonical encoding of
+    /// a non-identity Pallas point.
+    InvalidEphemeralKey(usize),
+}
+
+impl ValidationError {
+    /// Returns the index of the offending action, if this error is specific to a single
+    /// action.
+    pub fn action_index(&self) -> Option<usize> {
+        match self {
+            ValidationError::DuplicateNullifier { index, .. }
+            | ValidationError::InvalidEphemeralKey(index) => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::SpendsAndOutputsDisabled => {
+                write!(
+                    f,
+                    "Orchard bundle has actions but spends and outputs are disabled"
+                )
+            }
+            ValidationError::ValueBalanceRequiresSpends => {
+                write!(
+                    f,
+                    "Orchard bundle has positive value balance but spends are disabled"
+                )
+            }
+            ValidationError::ValueBalanceRequiresOutputs => {
+                write!(
+                    f,
+                    "Orchard bundle has negative value balance but outputs are disabled"
+                )
+            }
+            ValidationError::ValueBalanceOutOfRange(value_balance) => {
+                write!(f, "Orchard value balance {} is out of range", value_balance)
+            }
+            ValidationError::UnknownAnchor => write!(f, "Orchard anchor is unknown"),
+            ValidationError::DuplicateNullifier { index, first } => write!(
+                f,
+                "Orchard action {} reveals the same nullifier as action {}",
+                index, first
+            ),
+            ValidationError::InvalidEphemeralKey(index) => {
+                write!(f, "Orchard action {} has an invalid ephemeral key", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks the consensus rules for `bundle` that can be checked without verifying its
+/// proof or signatures.
+///
+/// `is_known_anchor` must return `true` if the given anchor is the root of the note
+/// commitment tree at the end of some earlier block.
+///
+/// The nullifiers, note commitments, value commitments and randomized verification keys
+/// of each action are canonically encoded by construction of their types, and are not
+/// checked again here.
+pub fn check_bundle<T: Authorization, V: Copy + Into<i64>>(
+    bundle: &Bundle<T, V>,
+    is_known_anchor: impl FnOnce(&Anchor) -> bool,
+) -> Result<(), ValidationError> {
+    let flags = bundle.flags();
+    if !(flags.spends_enabled() || flags.outputs_enabled()) {
+        return Err(ValidationError::SpendsAndOutputsDisabled);
+    }
+
+    let value_balance: i64 = (*bundle.value_balance()).into();
+    if !(-MAX_MONEY..=MAX_MONEY).contains(&value_balance) {
+        return Err(ValidationError::ValueBalanceOutOfRange(value_balance));
+    }
+    if !flags.spends_enabled() && value_balance > 0 {
+        return Err(ValidationError::ValueBalanceRequiresSpends);
+    }
+    if !flags.outputs_enabled() && value_balance < 0 {
+        return Err(ValidationError::ValueBalanceRequiresOutputs);
+    }
+
+    if !is_known_anchor(bundle.anchor()) {
+        return Err(ValidationError::UnknownAnchor);
+    }
+
+    let mut nullifiers = HashMap::with_capacity(bundle.actions().len());
+    for (index, action) in bundle.actions().iter().enumerate() {
+        if let Some(&first) = nullifiers.get(&action.nullifier().to_bytes()) {
+            return Err(ValidationError::DuplicateNullifier { index, first });
+        }
+        nullifiers.insert(action.nullifier().to_bytes(), index);
+
+        if EphemeralPublicKey::from_bytes(&action.encrypted_note().epk_bytes)
+            .is_none()
+            .into()
+        {
+            return Err(ValidationError::InvalidEphemeralKey(index));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use nonempty::NonEmpty;
+    use rand::rngs::OsRng;
+
+    use super::{check_bundle, ValidationError, MAX_MONEY};
+    use crate::{
+        builder::Builder,
+        bundle::{testing::Unauthorized, Action, Authorized, Bundle, Flags},
+        circuit::testing::FakeProver,
+        constants::MERKLE_DEPTH_ORCHARD,
+        keys::{FullViewingKey, SpendingKey},
+        tree::{Anchor, EMPTY_ROOTS},
+        value::NoteValue,
+    };
+
+    #[test]
+    fn bundle_rules() {
+        let mut rng = OsRng;
+        let anchor: Anchor = EMPTY_ROOTS[MERKLE_DEPTH_ORCHARD].into();
+        let recipient = FullViewingKey::from(&SpendingKey::random(&mut rng)).default_address();
+
+        let mut builder = Builder::new(Flags::from_parts(true, true), anchor);
+        builder
+            .add_recipient(None, recipient, NoteValue::from_raw(5000), None)
+            .unwrap();
+        let bundle: Bundle<Authorized, i64> = builder
+            .build(&mut rng)
+            .unwrap()
+            .0
+            .create_proof(&FakeProver)
+            .unwrap()
+            .prepare(&mut rng, [0; 32])
+            .finalize()
+            .unwrap();
+        let actions: Vec<Action<()>> = bundle
+            .actions()
+            .iter()
+            .map(|action| action.clone().map(|_| ()))
+            .collect();
+        let with_parts = |actions: Vec<Action<()>>, flags: Flags, value_balance: i64| {
+            Bundle::from_parts(
+                NonEmpty::from_vec(actions).unwrap(),
+                flags,
+                value_balance,
+                anchor,
+                Unauthorized,
+            )
+        };
+
+        // A bundle from the builder satisfies the rules.
+        assert_eq!(check_bundle(&bundle, |a| a == &anchor), Ok(()));
+        assert_eq!(
+            check_bundle(&bundle, |_| false),
+            Err(ValidationError::UnknownAnchor)
+        );
+
+        // The flags must be consistent with the value balance.
+        let check = |flags, value_balance| {
+            check_bundle(&with_parts(actions.clone(), flags, value_balance), |_| true)
+        };
+        assert_eq!(
+            check(Flags::from_parts(false, false), 0),
+            Err(ValidationError::SpendsAndOutputsDisabled)
+        );
+        assert_eq!(check(Flags::from_parts(false, true), -5000), Ok(()));
+        assert_eq!(
+            check(Flags::from_parts(false, true), 5000),
+            Err(ValidationError::ValueBalanceRequiresSpends)
+        );
+        assert_eq!(check(Flags::from_parts(true, false), 5000), Ok(()));
+        assert_eq!(
+            check(Flags::from_parts(true, false), -5000),
+            Err(ValidationError::ValueBalanceRequiresOutputs)
+        );
+        assert_eq!(check(Flags::from_parts(true, true), MAX_MONEY), Ok(()));
+        assert_eq!(
+            check(Flags::from_parts(true, true), -MAX_MONEY - 1),
+            Err(ValidationError::ValueBalanceOutOfRange(-MAX_MONEY - 1))
+        );
+
+        // Nullifiers must be unique within the bundle.
+        let mut duplicated = actions.clone();
+        duplicated.push(actions[1].clone());
+        let err = check_bundle(
+            &with_parts(duplicated, Flags::from_parts(true, true), -5000),
+            |_| true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::DuplicateNullifier {
+                index: actions.len(),
+                first: 1
+            }
+        );
+        assert_eq!(err.action_index(), Some(actions.len()));
+
+        // Ephemeral keys must not be the identity.
+        let mut invalid = actions.clone();
+        let action = invalid.remove(0);
+        let mut encrypted_note = action.encrypted_note().clone();
+        encrypted_note.epk_bytes = [0; 32];
+        invalid.push(Action::from_parts(
+            *action.nullifier(),
+            action.rk().clone(),
+            *action.cmx(),
+            encrypted_note,
+            action.cv_net().clone(),
+            (),
+        ));
+        assert_eq!(
+            check_bundle(
+                &with_parts(invalid, Flags::from_parts(true, true), -5000),
+                |_| true
+            ),
+            Err(ValidationError::InvalidEphemeralKey(actions.len() - 1))
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Key structures for Orchard.
 
-use std::convert::TryInto;
+use std::borrow::Cow;
+use std::convert::{TryFrom, TryInto};
 use std::mem;
 
 use aes::Aes256;
@@ -21,9 +22,11 @@ use crate::{
         commit_ivk, diversify_hash, extract_p, ka_orchard, prf_nf, to_base, to_scalar,
         NonIdentityPallasPoint, NonZeroPallasBase, NonZeroPallasScalar, PrfExpand,
     },
+    zip32::{self, ChildIndex, ExtendedSpendingKey},
 };
 
 const KDF_ORCHARD_PERSONALIZATION: &[u8; 16] = b"Zcash_OrchardKDF";
+const ZIP32_PURPOSE: u32 = 32;
 
 /// A spending key, from which all key material is derived.
 ///
@@ -65,6 +68,29 @@ impl SpendingKey {
         let ivk = KeyAgreementPrivateKey::derive_inner(&(&sk).into());
         CtOption::new(sk, !(ask.ct_is_zero() | ivk.is_none()))
     }
+
+    /// Returns the raw bytes of the spending key.
+    pub fn to_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Derives the Orchard spending key for the given seed, coin type, and account.
+    ///
+    /// This uses the [ZIP 32] derivation path `m/32'/coin_type'/account'`.
+    ///
+    /// [ZIP 32]: https://zips.z.cash/zip-0032
+    pub fn from_zip32_seed(
+        seed: &[u8],
+        coin_type: u32,
+        account: u32,
+    ) -> Result<Self, zip32::Error> {
+        let path = &[
+            ChildIndex::try_from(ZIP32_PURPOSE)?,
+            ChildIndex::try_from(coin_type)?,
+            ChildIndex::try_from(account)?,
+        ];
+        ExtendedSpendingKey::from_path(seed, path).map(|esk| esk.sk())
+    }
 }
 
 /// A spend authorizing key, used to create spend authorization signatures.
@@ -87,6 +113,26 @@ impl SpendAuthorizingKey {
     pub fn randomize(&self, randomizer: &pallas::Scalar) -> redpallas::SigningKey<SpendAuth> {
         self.0.randomize(randomizer)
     }
+
+    /// Parses a spend authorizing key from its byte encoding.
+    ///
+    /// Returns `None` unless the encoding is a valid $\mathsf{ask}$, i.e. a scalar whose
+    /// corresponding $\mathsf{ak}^\mathbb{P}$ has a y-coordinate with sign 0.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let ask = redpallas::SigningKey::<SpendAuth>::try_from(*bytes)
+            .ok()
+            .map(SpendAuthorizingKey)?;
+        if SpendValidatingKey::from(&ask).to_bytes()[31] & 0x80 == 0 {
+            Some(ask)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the byte encoding of this spend authorizing key.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        (&self.0).into()
+    }
 }
 
 impl From<&SpendingKey> for SpendAuthorizingKey {
@@ -138,6 +184,29 @@ impl SpendValidatingKey {
     pub fn randomize(&self, randomizer: &pallas::Scalar) -> redpallas::VerificationKey<SpendAuth> {
         self.0.randomize(randomizer)
     }
+
+    /// Parses a spend validating key from its byte encoding.
+    ///
+    /// Returns `None` unless the encoding is a valid $\mathsf{ak}^\mathbb{P}$, i.e. a
+    /// non-identity Pallas point whose y-coordinate has sign 0.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        // Structural validity checks for ak_P:
+        // - The point must not be the identity (which for Pallas is canonically encoded
+        //   as all-zeroes).
+        // - The sign of the y-coordinate must be positive.
+        if bytes != &[0; 32] && bytes[31] & 0x80 == 0 {
+            <redpallas::VerificationKey<SpendAuth>>::try_from(*bytes)
+                .ok()
+                .map(SpendValidatingKey)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the byte encoding of this spend validating key.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        (&self.0).into()
+    }
 }
 
 /// A key used to derive [`Nullifier`]s from [`Note`]s.
@@ -166,6 +235,16 @@ impl NullifierDerivingKey {
     pub(crate) fn prf_nf(&self, rho: pallas::Base) -> pallas::Base {
         prf_nf(self.0, rho)
     }
+
+    /// Parses a nullifier deriving key from its canonical byte encoding.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> CtOption<Self> {
+        pallas::Base::from_bytes(bytes).map(NullifierDerivingKey)
+    }
+
+    /// Returns the canonical byte encoding of this nullifier deriving key.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
 }
 
 /// The randomness for $\mathsf{Commit}^\mathsf{ivk}$.
@@ -186,6 +265,36 @@ impl CommitIvkRandomness {
     pub(crate) fn inner(&self) -> pallas::Scalar {
         self.0
     }
+
+    /// Parses $\mathsf{rivk}$ from its canonical byte encoding.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> CtOption<Self> {
+        pallas::Scalar::from_bytes(bytes).map(CommitIvkRandomness)
+    }
+
+    /// Returns the canonical byte encoding of $\mathsf{rivk}$.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+/// The scope of a viewing key or address.
+///
+/// A [`FullViewingKey`] has two scopes: an external scope, from which addresses are
+/// derived that are given out to other parties, and an internal scope, from which
+/// addresses are derived for change and other wallet-internal operations. Incoming and
+/// outgoing viewing keys, and addresses, derived in one scope are unlinkable to those
+/// derived in the other.
+///
+/// Defined in [ZIP 32: Orchard internal key derivation][orchardinternalfullviewingkey].
+///
+/// [orchardinternalfullviewingkey]: https://zips.z.cash/zip-0032#orchard-internal-key-derivation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// A scope used for wallet-external operations, namely deriving addresses to give to
+    /// other users in order to receive funds.
+    External,
+    /// A scope used for wallet-internal operations, such as creating change notes.
+    Internal,
 }
 
 /// A key that provides the capability to view incoming and outgoing transactions.
@@ -224,8 +333,79 @@ impl FullViewingKey {
         &self.nk
     }
 
-    pub(crate) fn rivk(&self) -> &CommitIvkRandomness {
-        &self.rivk
+    /// Returns the $\mathsf{rivk}$ for the given scope.
+    pub(crate) fn rivk(&self, scope: Scope) -> CommitIvkRandomness {
+        match scope {
+            Scope::External => self.rivk,
+            Scope::Internal => self.rivk_internal(),
+        }
+    }
+
+    /// Derives $\mathsf{rivk_{internal}}$ for this full viewing key.
+    ///
+    /// Defined in [ZIP 32: Orchard internal key derivation][orchardinternalfullviewingkey].
+    ///
+    /// [orchardinternalfullviewingkey]: https://zips.z.cash/zip-0032#orchard-internal-key-derivation
+    fn rivk_internal(&self) -> CommitIvkRandomness {
+        let k = self.rivk.0.to_bytes();
+        let ak = self.ak.to_bytes();
+        let nk = self.nk.to_bytes();
+        CommitIvkRandomness(to_scalar(
+            PrfExpand::OrchardRivkInternal.with_ad_slices(&k, &[&ak, &nk]),
+        ))
+    }
+
+    /// Derives the internal full viewing key, which shares $\mathsf{ak}$ and
+    /// $\mathsf{nk}$ with this key but uses $\mathsf{rivk_{internal}}$.
+    ///
+    /// The resulting key has an invalid $\mathsf{ivk}$ with negligible probability, so
+    /// we treat it as infallible in the same way as derivation from a [`SpendingKey`].
+    fn derive_internal(&self) -> Self {
+        FullViewingKey {
+            ak: self.ak.clone(),
+            nk: self.nk,
+            rivk: self.rivk_internal(),
+        }
+    }
+
+    /// Returns the full viewing key for the given scope.
+    fn scoped(&self, scope: Scope) -> Cow<'_, Self> {
+        match scope {
+            Scope::External => Cow::Borrowed(self),
+            Scope::Internal => Cow::Owned(self.derive_internal()),
+        }
+    }
+
+    /// Serializes the full viewing key as specified in [Zcash Protocol Spec § 5.6.4.4: Orchard Raw Full Viewing Keys][orchardrawfullviewingkeys]
+    ///
+    /// [orchardrawfullviewingkeys]: https://zips.z.cash/protocol/protocol.pdf#orchardfullviewingkeyencoding
+    pub fn to_bytes(&self) -> [u8; 96] {
+        let mut result = [0u8; 96];
+        result[..32].copy_from_slice(&self.ak.to_bytes());
+        result[32..64].copy_from_slice(&self.nk.to_bytes());
+        result[64..].copy_from_slice(&self.rivk.to_bytes());
+        result
+    }
+
+    /// Parses a full viewing key from its "raw" encoding as specified in [Zcash Protocol Spec § 5.6.4.4: Orchard Raw Full Viewing Keys][orchardrawfullviewingkeys]
+    ///
+    /// Returns `None` if `ak` is not a valid non-identity point with a y-coordinate of
+    /// sign 0, if `nk` or `rivk` are not canonically encoded, or if the resulting key
+    /// would have an invalid $\mathsf{ivk}$.
+    ///
+    /// [orchardrawfullviewingkeys]: https://zips.z.cash/protocol/protocol.pdf#orchardfullviewingkeyencoding
+    pub fn from_bytes(bytes: &[u8; 96]) -> Option<Self> {
+        let ak = SpendValidatingKey::from_bytes(bytes[..32].try_into().unwrap())?;
+        let nk = NullifierDerivingKey::from_bytes(bytes[32..64].try_into().unwrap());
+        let rivk = CommitIvkRandomness::from_bytes(bytes[64..].try_into().unwrap());
+        let nk: NullifierDerivingKey = Option::from(nk)?;
+        let rivk: CommitIvkRandomness = Option::from(rivk)?;
+
+        let fvk = FullViewingKey { ak, nk, rivk };
+
+        // If ivk is 0 or ⊥, this full viewing key is invalid.
+        let _: NonZeroPallasBase = Option::from(KeyAgreementPrivateKey::derive_inner(&fvk))?;
+        Some(fvk)
     }
 
     /// Defined in [Zcash Protocol Spec § 4.2.3: Orchard Key Components][orchardkeycomponents].
@@ -241,20 +421,40 @@ impl FullViewingKey {
         )
     }
 
-    /// Returns the default payment address for this key.
+    /// Returns the external-scope default payment address for this key.
     pub fn default_address(&self) -> Address {
         IncomingViewingKey::from(self).default_address()
     }
 
-    /// Returns the payment address for this key at the given index.
-    pub fn address_at(&self, j: impl Into<DiversifierIndex>) -> Address {
-        IncomingViewingKey::from(self).address_at(j)
+    /// Returns the payment address for this key at the given index, in the given scope.
+    pub fn address_at(&self, j: impl Into<DiversifierIndex>, scope: Scope) -> Address {
+        self.to_ivk(scope).address_at(j)
     }
 
-    /// Returns the payment address for this key corresponding to the given diversifier.
-    pub fn address(&self, d: Diversifier) -> Address {
+    /// Returns the payment address for this key corresponding to the given diversifier,
+    /// in the given scope.
+    pub fn address(&self, d: Diversifier, scope: Scope) -> Address {
         // Shortcut: we don't need to derive DiversifierKey.
-        KeyAgreementPrivateKey::from(self).address(d)
+        KeyAgreementPrivateKey::from(&*self.scoped(scope)).address(d)
+    }
+
+    /// Returns the incoming viewing key for the given scope.
+    pub fn to_ivk(&self, scope: Scope) -> IncomingViewingKey {
+        IncomingViewingKey::from(&*self.scoped(scope))
+    }
+
+    /// Returns the outgoing viewing key for the given scope.
+    pub fn to_ovk(&self, scope: Scope) -> OutgoingViewingKey {
+        OutgoingViewingKey::from(&*self.scoped(scope))
+    }
+
+    /// Returns the scope of the given address, or `None` if the address was not
+    /// derived from this full viewing key.
+    pub fn scope_for_address(&self, address: &Address) -> Option<Scope> {
+        [Scope::External, Scope::Internal]
+            .iter()
+            .cloned()
+            .find(|&scope| &self.address(address.diversifier(), scope) == address)
     }
 }
 
@@ -273,9 +473,22 @@ impl From<&FullViewingKey> for DiversifierKey {
 }
 
 /// The index for a particular diversifier.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct DiversifierIndex([u8; 11]);
 
+impl From<[u8; 11]> for DiversifierIndex {
+    fn from(j_bytes: [u8; 11]) -> Self {
+        DiversifierIndex(j_bytes)
+    }
+}
+
+impl DiversifierIndex {
+    /// Returns the raw bytes of the diversifier index.
+    pub fn to_bytes(&self) -> &[u8; 11] {
+        &self.0
+    }
+}
+
 macro_rules! di_from {
     ($n:ident) => {
         impl From<$n> for DiversifierIndex {
@@ -305,6 +518,19 @@ impl DiversifierKey {
             .unwrap();
         Diversifier(enc.to_bytes_le().try_into().unwrap())
     }
+
+    /// Returns the diversifier index obtained by decrypting the diversifier.
+    ///
+    /// Every diversifier has a corresponding index, so this does not check whether the
+    /// diversifier was actually produced by this key. Use
+    /// [`IncomingViewingKey::diversifier_index`] to check an address against a key.
+    pub fn diversifier_index(&self, d: &Diversifier) -> DiversifierIndex {
+        let ff = FF1::<Aes256>::new(&self.0, 2).expect("valid radix");
+        let dec = ff
+            .decrypt(&[], &BinaryNumeralString::from_bytes_le(&d.0[..]))
+            .unwrap();
+        DiversifierIndex(dec.to_bytes_le().try_into().unwrap())
+    }
 }
 
 /// A diversifier that can be used to derive a specific [`Address`] from a
@@ -317,7 +543,8 @@ impl DiversifierKey {
 pub struct Diversifier([u8; 11]);
 
 impl Diversifier {
-    pub(crate) fn from_bytes(d: [u8; 11]) -> Self {
+    /// Reads a diversifier from a byte array.
+    pub fn from_bytes(d: [u8; 11]) -> Self {
         Diversifier(d)
     }
 
@@ -389,6 +616,8 @@ pub struct IncomingViewingKey {
 }
 
 impl From<&FullViewingKey> for IncomingViewingKey {
+    /// Derives the external-scope incoming viewing key. Use [`FullViewingKey::to_ivk`] to
+    /// derive the key for a specific scope.
     fn from(fvk: &FullViewingKey) -> Self {
         IncomingViewingKey {
             dk: fvk.into(),
@@ -408,6 +637,16 @@ impl IncomingViewingKey {
         })
     }
 
+    /// Serializes an Orchard incoming viewing key to its raw encoding.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut result = [0u8; 64];
+        result[..32].copy_from_slice(&self.dk.0);
+        // ivk is always constructed from a base field element, so its scalar encoding
+        // is also the canonical base field encoding.
+        result[32..].copy_from_slice(&self.ivk.0.to_bytes());
+        result
+    }
+
     /// Returns the default payment address for this key.
     pub fn default_address(&self) -> Address {
         self.address(self.dk.default_diversifier())
@@ -422,6 +661,16 @@ impl IncomingViewingKey {
     pub fn address(&self, d: Diversifier) -> Address {
         self.ivk.address(d)
     }
+
+    /// Returns the diversifier index at which the given address was derived from this
+    /// key, or `None` if the address was not derived from this key.
+    pub fn diversifier_index(&self, addr: &Address) -> Option<DiversifierIndex> {
+        if &self.address(addr.diversifier()) == addr {
+            Some(self.dk.diversifier_index(&addr.diversifier()))
+        } else {
+            None
+        }
+    }
 }
 
 /// A key that provides the capability to recover outgoing transaction information from
@@ -437,6 +686,8 @@ impl IncomingViewingKey {
 pub struct OutgoingViewingKey([u8; 32]);
 
 impl From<&FullViewingKey> for OutgoingViewingKey {
+    /// Derives the external-scope outgoing viewing key. Use [`FullViewingKey::to_ovk`] to
+    /// derive the key for a specific scope.
     fn from(fvk: &FullViewingKey) -> Self {
         fvk.derive_dk_ovk().1
     }
@@ -669,6 +920,25 @@ mod tests {
         assert!(bool::from(
             EphemeralPublicKey::from_bytes(&[0xff; 32]).is_none()
         ));
+        assert!(FullViewingKey::from_bytes(&[0xff; 96]).is_none());
+        // ak must not be the identity.
+        assert!(FullViewingKey::from_bytes(&[0; 96]).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn fvk_encoding(sk in arb_spending_key()) {
+            let fvk = FullViewingKey::from(&sk);
+            let encoded = fvk.to_bytes();
+            let decoded = FullViewingKey::from_bytes(&encoded).unwrap();
+            assert_eq!(decoded.to_bytes(), encoded);
+            assert_eq!(decoded.default_address(), fvk.default_address());
+
+            // Flipping the sign of ak produces an invalid encoding.
+            let mut negated = encoded;
+            negated[31] |= 0x80;
+            assert!(FullViewingKey::from_bytes(&negated).is_none());
+        }
     }
 
     proptest! {
@@ -688,6 +958,66 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn internal_scope(sk in arb_spending_key()) {
+            let fvk = FullViewingKey::from(&sk);
+            let internal = fvk.derive_internal();
+
+            // The internal key shares ak and nk, but nothing else.
+            assert_eq!(&fvk.to_bytes()[..64], &internal.to_bytes()[..64]);
+            assert_ne!(&fvk.to_bytes()[64..], &internal.to_bytes()[64..]);
+            assert_ne!(
+                fvk.to_ivk(Scope::External).to_bytes(),
+                fvk.to_ivk(Scope::Internal).to_bytes()
+            );
+            assert_ne!(
+                fvk.to_ovk(Scope::External).as_ref(),
+                fvk.to_ovk(Scope::Internal).as_ref()
+            );
+            assert_eq!(
+                fvk.to_ivk(Scope::External).to_bytes(),
+                IncomingViewingKey::from(&fvk).to_bytes()
+            );
+
+            let external_addr = fvk.address_at(0u32, Scope::External);
+            let internal_addr = fvk.address_at(0u32, Scope::Internal);
+            assert_eq!(external_addr, fvk.default_address());
+            assert_ne!(external_addr, internal_addr);
+            assert_eq!(fvk.scope_for_address(&external_addr), Some(Scope::External));
+            assert_eq!(fvk.scope_for_address(&internal_addr), Some(Scope::Internal));
+
+            // An address for the internal scope of some other key is not recognised.
+            let other = FullViewingKey::from(&SpendingKey::from_bytes([7; 32]).unwrap());
+            assert_eq!(
+                fvk.scope_for_address(&other.address_at(0u32, Scope::Internal)),
+                None
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn diversifier_index(sk in arb_spending_key(), j in any::<u64>()) {
+            let fvk = FullViewingKey::from(&sk);
+            let dk = DiversifierKey::from(&fvk);
+            let j = DiversifierIndex::from(j);
+
+            let d = dk.get(j);
+            assert_eq!(dk.diversifier_index(&d), j);
+
+            let external_ivk = fvk.to_ivk(Scope::External);
+            let internal_ivk = fvk.to_ivk(Scope::Internal);
+            let addr = fvk.address_at(j, Scope::External);
+            assert_eq!(external_ivk.diversifier_index(&addr), Some(j));
+            assert_eq!(internal_ivk.diversifier_index(&addr), None);
+
+            let internal_addr = fvk.address_at(j, Scope::Internal);
+            assert_eq!(internal_ivk.diversifier_index(&internal_addr), Some(j));
+            assert_eq!(external_ivk.diversifier_index(&internal_addr), None);
+        }
+    }
+
     #[test]
     fn test_vectors() {
         for tv in crate::test_vectors::keys::test_vectors() {
@@ -710,12 +1040,31 @@ mod tests {
             assert_eq!(fvk.nk().0.to_repr(), tv.nk);
             assert_eq!(fvk.rivk.0.to_repr(), tv.rivk);
 
+            let fvk_bytes = fvk.to_bytes();
+            assert_eq!(&fvk_bytes[..32], &tv.ak[..]);
+            assert_eq!(&fvk_bytes[32..64], &tv.nk[..]);
+            assert_eq!(&fvk_bytes[64..], &tv.rivk[..]);
+            assert_eq!(
+                FullViewingKey::from_bytes(&fvk_bytes).unwrap().to_bytes(),
+                fvk_bytes
+            );
+
             let ivk: KeyAgreementPrivateKey = (&fvk).into();
             assert_eq!(ivk.0.to_repr(), tv.ivk);
 
+            let ivk_bytes = IncomingViewingKey::from(&fvk).to_bytes();
+            assert_eq!(&ivk_bytes[..32], &tv.dk[..]);
+            assert_eq!(&ivk_bytes[32..], &tv.ivk[..]);
+            assert_eq!(
+                IncomingViewingKey::from_bytes(&ivk_bytes)
+                    .unwrap()
+                    .to_bytes(),
+                ivk_bytes
+            );
+
             let diversifier = Diversifier(tv.default_d);
 
-            let addr = fvk.address(diversifier);
+            let addr = fvk.address(diversifier, Scope::External);
             assert_eq!(&addr.pk_d().to_bytes(), &tv.default_pk_d);
 
             let rho = Nullifier::from_bytes(&tv.note_rho).unwrap();
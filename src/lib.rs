@@ -20,14 +20,19 @@ mod address;
 pub mod builder;
 pub mod bundle;
 pub mod circuit;
+pub mod consensus;
 mod constants;
 pub mod keys;
+pub mod memo;
 pub mod note;
 pub mod note_encryption;
 pub mod primitives;
+mod serialization;
 mod spec;
 pub mod tree;
+pub mod unified;
 pub mod value;
+pub mod zip32;
 
 #[cfg(test)]
 mod test_vectors;
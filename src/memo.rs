@@ -0,0 +1,302 @@
+//! Memo fields of Orchard notes.
+//!
+//! The format of memo fields is defined in [ZIP 302: Standardized Memo Field Format][zip302].
+//!
+//! [zip302]: https://zips.z.cash/zip-0302
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+use std::str::{self, FromStr};
+
+/// The size of a memo field, in bytes.
+pub const MEMO_SIZE: usize = 512;
+
+/// Errors that can occur when parsing or constructing memos.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The memo is a text memo that is not valid UTF-8.
+    InvalidUtf8(str::Utf8Error),
+    /// The given data is longer than a memo field.
+    TooLong(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidUtf8(e) => write!(f, "Invalid UTF-8: {}", e),
+            Error::TooLong(n) => write!(f, "Memo length {} is larger than maximum of 512", n),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The unencrypted memo bytes received alongside a shielded note.
+///
+/// This is the raw contents of the memo field, which can be interpreted with
+/// [`Memo::try_from`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct MemoBytes(Box<[u8; MEMO_SIZE]>);
+
+impl fmt::Debug for MemoBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MemoBytes(")?;
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Default for MemoBytes {
+    fn default() -> Self {
+        MemoBytes::empty()
+    }
+}
+
+impl MemoBytes {
+    /// Creates a `MemoBytes` indicating that no memo is present.
+    pub fn empty() -> Self {
+        let mut bytes = [0; MEMO_SIZE];
+        bytes[0] = 0xf6;
+        MemoBytes(Box::new(bytes))
+    }
+
+    /// Creates a `MemoBytes` from a slice, zero-padding it to [`MEMO_SIZE`].
+    ///
+    /// Returns an error if the slice is longer than [`MEMO_SIZE`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > MEMO_SIZE {
+            return Err(Error::TooLong(bytes.len()));
+        }
+
+        let mut memo = [0; MEMO_SIZE];
+        memo[..bytes.len()].copy_from_slice(bytes);
+        Ok(MemoBytes(Box::new(memo)))
+    }
+
+    /// Returns the raw byte array containing the memo bytes, including null padding.
+    pub fn as_array(&self) -> &[u8; MEMO_SIZE] {
+        &self.0
+    }
+
+    /// Returns a slice of the raw bytes, excluding null padding.
+    pub fn as_slice(&self) -> &[u8] {
+        let len = self
+            .0
+            .iter()
+            .rposition(|&b| b != 0)
+            .map(|i| i + 1)
+            .unwrap_or_default();
+
+        &self.0[..len]
+    }
+}
+
+/// Type-safe wrapper around a `String` that is guaranteed to be at most
+/// [`MEMO_SIZE`] bytes when encoded as UTF-8.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextMemo(String);
+
+impl Deref for TextMemo {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An unencrypted memo received alongside a shielded note, interpreted according to
+/// [ZIP 302][zip302].
+///
+/// [zip302]: https://zips.z.cash/zip-0302
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Memo {
+    /// An empty memo field.
+    Empty,
+    /// A memo field containing a UTF-8 string.
+    Text(TextMemo),
+    /// A memo field in a format reserved for future use, which we can't parse.
+    Future(MemoBytes),
+    /// A memo field containing arbitrary bytes.
+    Arbitrary(Box<[u8; MEMO_SIZE - 1]>),
+}
+
+impl Default for Memo {
+    fn default() -> Self {
+        Memo::Empty
+    }
+}
+
+impl Memo {
+    /// Parses a memo from the raw contents of a memo field.
+    ///
+    /// Returns an error if the provided slice is longer than [`MEMO_SIZE`], or if it
+    /// encodes a text memo that is not valid UTF-8.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        MemoBytes::from_bytes(bytes).and_then(|memo| Memo::try_from(&memo))
+    }
+
+    /// Serializes the memo as the raw contents of a memo field.
+    pub fn encode(&self) -> MemoBytes {
+        self.into()
+    }
+}
+
+impl TryFrom<&MemoBytes> for Memo {
+    type Error = Error;
+
+    /// Parses a `Memo` from its ZIP 302 serialization.
+    fn try_from(bytes: &MemoBytes) -> Result<Self, Self::Error> {
+        match bytes.0[0] {
+            0xf6 if bytes.0.iter().skip(1).all(|&b| b == 0) => Ok(Memo::Empty),
+            0xff => {
+                let mut data = [0; MEMO_SIZE - 1];
+                data.copy_from_slice(&bytes.0[1..]);
+                Ok(Memo::Arbitrary(Box::new(data)))
+            }
+            b if b <= 0xf4 => str::from_utf8(bytes.as_slice())
+                .map(|text| Memo::Text(TextMemo(text.to_owned())))
+                .map_err(Error::InvalidUtf8),
+            _ => Ok(Memo::Future(bytes.clone())),
+        }
+    }
+}
+
+impl TryFrom<MemoBytes> for Memo {
+    type Error = Error;
+
+    fn try_from(bytes: MemoBytes) -> Result<Self, Self::Error> {
+        Memo::try_from(&bytes)
+    }
+}
+
+impl From<&Memo> for MemoBytes {
+    /// Serializes the `Memo` per ZIP 302.
+    fn from(memo: &Memo) -> Self {
+        match memo {
+            Memo::Empty => MemoBytes::empty(),
+            Memo::Text(text) => {
+                let mut bytes = [0; MEMO_SIZE];
+                bytes[..text.0.len()].copy_from_slice(text.0.as_bytes());
+                MemoBytes(Box::new(bytes))
+            }
+            Memo::Future(bytes) => bytes.clone(),
+            Memo::Arbitrary(data) => {
+                let mut bytes = [0; MEMO_SIZE];
+                bytes[0] = 0xff;
+                bytes[1..].copy_from_slice(&data[..]);
+                MemoBytes(Box::new(bytes))
+            }
+        }
+    }
+}
+
+impl From<Memo> for MemoBytes {
+    fn from(memo: Memo) -> Self {
+        (&memo).into()
+    }
+}
+
+impl FromStr for Memo {
+    type Err = Error;
+
+    /// Returns a `Memo` containing the given string, or an error if the string does not
+    /// fit into a memo field.
+    ///
+    /// The empty string is parsed as [`Memo::Empty`].
+    fn from_str(memo: &str) -> Result<Self, Self::Err> {
+        if memo.is_empty() {
+            Ok(Memo::Empty)
+        } else if memo.len() <= MEMO_SIZE {
+            Ok(Memo::Text(TextMemo(memo.to_owned())))
+        } else {
+            Err(Error::TooLong(memo.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use super::{Error, Memo, MemoBytes, MEMO_SIZE};
+
+    #[test]
+    fn memo_from_str() {
+        assert_eq!(Memo::from_str("").unwrap(), Memo::Empty);
+        assert_eq!(
+            Memo::from_str(&"x".repeat(MEMO_SIZE + 1)),
+            Err(Error::TooLong(MEMO_SIZE + 1)),
+        );
+
+        let memo = Memo::from_str("thanks for the coffee").unwrap();
+        let bytes = memo.encode();
+        assert_eq!(bytes.as_slice(), b"thanks for the coffee");
+        assert_eq!(Memo::try_from(&bytes).unwrap(), memo);
+
+        // Text memos that use the full memo field round-trip.
+        let memo = Memo::from_str(&"\u{1f4b0}".repeat(MEMO_SIZE / 4)).unwrap();
+        assert_eq!(Memo::try_from(memo.encode()).unwrap(), memo);
+    }
+
+    #[test]
+    fn memo_classification() {
+        // The empty memo.
+        assert_eq!(Memo::try_from(MemoBytes::empty()).unwrap(), Memo::Empty);
+        assert_eq!(Memo::default().encode(), MemoBytes::empty());
+
+        // Trailing zeros are stripped from text memos.
+        assert_eq!(
+            Memo::from_bytes(b"abc\0\0").unwrap(),
+            Memo::from_str("abc").unwrap(),
+        );
+
+        // Invalid UTF-8 is rejected.
+        assert!(matches!(
+            Memo::from_bytes(&[0xc3, 0x28]),
+            Err(Error::InvalidUtf8(_))
+        ));
+
+        // Arbitrary data.
+        let mut data = [0; MEMO_SIZE];
+        data[0] = 0xff;
+        data[1] = 0x42;
+        let memo = Memo::from_bytes(&data).unwrap();
+        match &memo {
+            Memo::Arbitrary(contents) => assert_eq!(contents[0], 0x42),
+            _ => panic!("expected arbitrary data"),
+        }
+        assert_eq!(memo.encode().as_array(), &data);
+
+        // Reserved formats, including 0xf6 followed by non-zero bytes.
+        for &first in &[0xf5, 0xf6, 0xf7, 0xfe] {
+            let mut data = [0; MEMO_SIZE];
+            data[0] = first;
+            data[MEMO_SIZE - 1] = 0x01;
+            let bytes = MemoBytes::from_bytes(&data).unwrap();
+            assert_eq!(Memo::try_from(&bytes).unwrap(), Memo::Future(bytes));
+        }
+
+        // Data longer than a memo field is rejected.
+        assert_eq!(
+            MemoBytes::from_bytes(&[0; MEMO_SIZE + 1]),
+            Err(Error::TooLong(MEMO_SIZE + 1)),
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn memo_bytes_round_trip(data in vec(any::<u8>(), 0..=MEMO_SIZE)) {
+            let bytes = MemoBytes::from_bytes(&data).unwrap();
+            if let Ok(memo) = Memo::try_from(&bytes) {
+                assert_eq!(memo.encode(), bytes);
+            }
+        }
+    }
+}
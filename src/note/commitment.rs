@@ -2,6 +2,7 @@ use std::iter;
 
 use bitvec::{array::BitArray, order::Lsb0};
 use ff::PrimeFieldBits;
+use group::GroupEncoding;
 use pasta_curves::{arithmetic::FieldExt, pallas};
 use subtle::{ConstantTimeEq, CtOption};
 
@@ -19,6 +20,16 @@ impl NoteCommitTrapdoor {
     pub(crate) fn inner(&self) -> pallas::Scalar {
         self.0
     }
+
+    /// Parses a note commitment trapdoor from its canonical byte encoding.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> CtOption<Self> {
+        pallas::Scalar::from_bytes(bytes).map(NoteCommitTrapdoor)
+    }
+
+    /// Returns the canonical byte encoding of this note commitment trapdoor.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
 }
 
 /// A commitment to a note.
@@ -29,6 +40,16 @@ impl NoteCommitment {
     pub(crate) fn inner(&self) -> pallas::Point {
         self.0
     }
+
+    /// Parses a note commitment from its byte encoding.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> CtOption<Self> {
+        pallas::Point::from_bytes(bytes).map(NoteCommitment)
+    }
+
+    /// Returns the byte encoding of this note commitment.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
 }
 
 impl NoteCommitment {
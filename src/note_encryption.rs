@@ -4,8 +4,9 @@ use std::{convert::TryInto, fmt};
 
 use blake2b_simd::{Hash, Params};
 use halo2::arithmetic::FieldExt;
+use rayon::prelude::*;
 use zcash_note_encryption::{
-    Domain, EphemeralKeyBytes, NotePlaintextBytes, NoteValidity, OutPlaintextBytes,
+    batch, Domain, EphemeralKeyBytes, NotePlaintextBytes, NoteValidity, OutPlaintextBytes,
     OutgoingCipherKey, ShieldedOutput, COMPACT_NOTE_SIZE, NOTE_PLAINTEXT_SIZE, OUT_PLAINTEXT_SIZE,
 };
 
@@ -15,6 +16,7 @@ use crate::{
         DiversifiedTransmissionKey, Diversifier, EphemeralPublicKey, EphemeralSecretKey,
         IncomingViewingKey, OutgoingViewingKey, SharedSecret,
     },
+    memo::MemoBytes,
     note::{ExtractedNoteCommitment, Nullifier, RandomSeed},
     spec::diversify_hash,
     value::{NoteValue, ValueCommitment},
@@ -91,6 +93,12 @@ impl OrchardDomain {
             rho: *act.nullifier(),
         }
     }
+
+    /// Constructs a domain that can be used to trial-decrypt this compact action's
+    /// output note.
+    pub fn for_compact_action(act: &CompactAction) -> Self {
+        OrchardDomain { rho: act.nullifier }
+    }
 }
 
 impl Domain for OrchardDomain {
@@ -106,7 +114,7 @@ impl Domain for OrchardDomain {
     type ValueCommitment = ValueCommitment;
     type ExtractedCommitment = ExtractedNoteCommitment;
     type ExtractedCommitmentBytes = [u8; 32];
-    type Memo = [u8; 512]; // TODO use a more interesting type
+    type Memo = MemoBytes;
 
     fn derive_esk(note: &Self::Note) -> Option<Self::EphemeralSecretKey> {
         Some(note.esk())
@@ -161,10 +169,10 @@ impl Domain for OrchardDomain {
     ) -> NotePlaintextBytes {
         let mut np = [0; NOTE_PLAINTEXT_SIZE];
         np[0] = 0x02;
-        np[1..12].copy_from_slice(note.recipient().diversifer().as_array());
+        np[1..12].copy_from_slice(note.recipient().diversifier().as_array());
         np[12..20].copy_from_slice(&note.value().to_bytes());
         np[20..52].copy_from_slice(note.rseed().to_bytes());
-        np[52..].copy_from_slice(memo);
+        np[52..].copy_from_slice(memo.as_array());
         NotePlaintextBytes(np)
     }
 
@@ -238,9 +246,7 @@ impl Domain for OrchardDomain {
     }
 
     fn extract_memo(&self, plaintext: &[u8]) -> Self::Memo {
-        plaintext[COMPACT_NOTE_SIZE..NOTE_PLAINTEXT_SIZE]
-            .try_into()
-            .unwrap()
+        MemoBytes::from_bytes(&plaintext[COMPACT_NOTE_SIZE..NOTE_PLAINTEXT_SIZE]).unwrap()
     }
 
     fn extract_pk_d(
@@ -272,8 +278,55 @@ impl<T> ShieldedOutput<OrchardDomain> for Action<T> {
     }
 }
 
+impl<T> ShieldedOutput<OrchardDomain> for &Action<T> {
+    fn ephemeral_key(&self) -> EphemeralKeyBytes {
+        (*self).ephemeral_key()
+    }
+
+    fn cmstar_bytes(&self) -> [u8; 32] {
+        (*self).cmstar_bytes()
+    }
+
+    fn enc_ciphertext(&self) -> &[u8] {
+        (*self).enc_ciphertext()
+    }
+}
+
+/// The number of compact actions that are trial-decrypted together by
+/// [`CompactAction::scan`], sharing a single batched key derivation.
+const SCAN_BATCH_SIZE: usize = 1024;
+
+/// Trial-decrypts `n_outputs` outputs with each of the given incoming viewing keys in
+/// turn, where `try_decrypt` decrypts every output with a single key at once.
+///
+/// Returns an `(output index, key index, result)` tuple for each output that could be
+/// decrypted, in order of output index. If an output can be decrypted with more than one
+/// of the keys, the first such key is used.
+pub(crate) fn decrypt_with_first_key<R>(
+    n_outputs: usize,
+    ivks: &[IncomingViewingKey],
+    try_decrypt: impl Fn(&IncomingViewingKey) -> Vec<Option<R>>,
+) -> Vec<(usize, usize, R)> {
+    let mut decrypted = vec![];
+    let mut found = vec![false; n_outputs];
+    for (key_idx, ivk) in ivks.iter().enumerate() {
+        for (output_idx, result) in try_decrypt(ivk).into_iter().enumerate() {
+            if let Some(result) = result {
+                if !found[output_idx] {
+                    found[output_idx] = true;
+                    decrypted.push((output_idx, key_idx, result));
+                }
+            }
+        }
+    }
+
+    decrypted.sort_by_key(|(output_idx, ..)| *output_idx);
+    decrypted
+}
+
 /// A compact Action for light clients.
 pub struct CompactAction {
+    nullifier: Nullifier,
     ephemeral_key: EphemeralKeyBytes,
     cmx: ExtractedNoteCommitment,
     enc_ciphertext: [u8; 52],
@@ -288,6 +341,7 @@ impl fmt::Debug for CompactAction {
 impl<T> From<&Action<T>> for CompactAction {
     fn from(action: &Action<T>) -> Self {
         CompactAction {
+            nullifier: *action.nullifier(),
             ephemeral_key: action.ephemeral_key(),
             cmx: *action.cmx(),
             enc_ciphertext: action.encrypted_note().enc_ciphertext[..52]
@@ -311,21 +365,100 @@ impl ShieldedOutput<OrchardDomain> for CompactAction {
     }
 }
 
+impl ShieldedOutput<OrchardDomain> for &CompactAction {
+    fn ephemeral_key(&self) -> EphemeralKeyBytes {
+        (*self).ephemeral_key()
+    }
+
+    fn cmstar_bytes(&self) -> [u8; 32] {
+        (*self).cmstar_bytes()
+    }
+
+    fn enc_ciphertext(&self) -> &[u8] {
+        (*self).enc_ciphertext()
+    }
+}
+
+impl CompactAction {
+    /// Constructs a compact action from its constituent parts, as provided to light
+    /// clients.
+    pub fn from_parts(
+        nullifier: Nullifier,
+        cmx: ExtractedNoteCommitment,
+        ephemeral_key: EphemeralKeyBytes,
+        enc_ciphertext: [u8; 52],
+    ) -> Self {
+        CompactAction {
+            nullifier,
+            ephemeral_key,
+            cmx,
+            enc_ciphertext,
+        }
+    }
+
+    /// Returns the nullifier of the note being spent by this action.
+    pub fn nullifier(&self) -> Nullifier {
+        self.nullifier
+    }
+
+    /// Trial-decrypts the given compact actions with each of the given incoming viewing
+    /// keys.
+    ///
+    /// The actions are split into batches that are decrypted in parallel on the global
+    /// thread pool; within each batch, the shared secrets for every action are derived
+    /// together with [`OrchardDomain`]'s batched key derivation.
+    ///
+    /// Returns a `(action index, key index, note, recipient)` tuple for each action that
+    /// could be decrypted, in order of action index. If an action can be decrypted with
+    /// more than one of the keys, the first such key is used.
+    pub fn scan(
+        actions: &[CompactAction],
+        ivks: &[IncomingViewingKey],
+    ) -> Vec<(usize, usize, Note, Address)> {
+        actions
+            .par_chunks(SCAN_BATCH_SIZE)
+            .enumerate()
+            .map(|(chunk_idx, chunk)| {
+                let offset = chunk_idx * SCAN_BATCH_SIZE;
+                let outputs: Vec<_> = chunk
+                    .iter()
+                    .map(|action| (OrchardDomain::for_compact_action(action), action))
+                    .collect();
+
+                decrypt_with_first_key(outputs.len(), ivks, |ivk| {
+                    batch::try_compact_note_decryption(std::slice::from_ref(ivk), &outputs)
+                })
+                .into_iter()
+                .map(|(i, key_idx, (note, recipient))| (offset + i, key_idx, note, recipient))
+                .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::convert::TryInto;
+
     use rand::rngs::OsRng;
     use zcash_note_encryption::{
         try_compact_note_decryption, try_note_decryption, try_output_recovery_with_ovk,
         EphemeralKeyBytes,
     };
 
-    use super::{prf_ock_orchard, CompactAction, OrchardDomain, OrchardNoteEncryption};
+    use super::{
+        prf_ock_orchard, CompactAction, OrchardDomain, OrchardNoteEncryption, SCAN_BATCH_SIZE,
+    };
     use crate::{
         bundle::Action,
         keys::{
             DiversifiedTransmissionKey, Diversifier, EphemeralSecretKey, IncomingViewingKey,
             OutgoingViewingKey,
         },
+        memo::MemoBytes,
         note::{ExtractedNoteCommitment, Nullifier, RandomSeed, TransmittedNoteCiphertext},
         primitives::redpallas,
         value::{NoteValue, ValueCommitment},
@@ -402,7 +535,7 @@ mod tests {
                 Some((decrypted_note, decrypted_to, decrypted_memo)) => {
                     assert_eq!(decrypted_note, note);
                     assert_eq!(decrypted_to, recipient);
-                    assert_eq!(&decrypted_memo[..], &tv.memo[..]);
+                    assert_eq!(decrypted_memo.as_array(), &tv.memo);
                 }
                 None => panic!("Note decryption failed"),
             }
@@ -419,7 +552,7 @@ mod tests {
                 Some((decrypted_note, decrypted_to, decrypted_memo)) => {
                     assert_eq!(decrypted_note, note);
                     assert_eq!(decrypted_to, recipient);
-                    assert_eq!(&decrypted_memo[..], &tv.memo[..]);
+                    assert_eq!(decrypted_memo.as_array(), &tv.memo);
                 }
                 None => panic!("Output recovery failed"),
             }
@@ -428,7 +561,13 @@ mod tests {
             // Test encryption
             //
 
-            let ne = OrchardNoteEncryption::new_with_esk(esk, Some(ovk), note, recipient, tv.memo);
+            let ne = OrchardNoteEncryption::new_with_esk(
+                esk,
+                Some(ovk),
+                note,
+                recipient,
+                MemoBytes::from_bytes(&tv.memo).unwrap(),
+            );
 
             assert_eq!(ne.encrypt_note_plaintext().as_ref(), &tv.c_enc[..]);
             assert_eq!(
@@ -437,4 +576,46 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn compact_action_scan() {
+        let mut rng = OsRng;
+
+        let compact_action = |note: Note| {
+            let encryptor =
+                OrchardNoteEncryption::new(None, note, note.recipient(), MemoBytes::empty());
+            CompactAction::from_parts(
+                note.rho(),
+                note.commitment().into(),
+                encryptor.epk().to_bytes(),
+                encryptor.encrypt_note_plaintext()[..52].try_into().unwrap(),
+            )
+        };
+
+        // Place notes for two different keys in different scanning batches.
+        let (_, fvk_a, note_a) = Note::dummy(&mut rng, None);
+        let (_, fvk_b, note_b) = Note::dummy(&mut rng, None);
+        let mut actions: Vec<_> = (0..SCAN_BATCH_SIZE + 10)
+            .map(|_| compact_action(Note::dummy(&mut rng, None).2))
+            .collect();
+        actions[3] = compact_action(note_b);
+        actions[SCAN_BATCH_SIZE + 5] = compact_action(note_a);
+
+        let ivks = [
+            IncomingViewingKey::from(&fvk_a),
+            IncomingViewingKey::from(&fvk_b),
+        ];
+        let decrypted = CompactAction::scan(&actions, &ivks);
+        assert_eq!(decrypted.len(), 2);
+
+        assert_eq!(decrypted[0].0, 3);
+        assert_eq!(decrypted[0].1, 1);
+        assert_eq!(decrypted[0].2, note_b);
+        assert_eq!(decrypted[0].3, note_b.recipient());
+
+        assert_eq!(decrypted[1].0, SCAN_BATCH_SIZE + 5);
+        assert_eq!(decrypted[1].1, 0);
+        assert_eq!(decrypted[1].2, note_a);
+        assert_eq!(decrypted[1].3, note_a.recipient());
+    }
 }
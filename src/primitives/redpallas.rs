@@ -7,6 +7,8 @@ use rand::{CryptoRng, RngCore};
 
 pub use reddsa::batch;
 
+pub mod frost;
+
 #[cfg(test)]
 use rand::rngs::OsRng;
 
@@ -91,6 +93,13 @@ impl<'a, T: SigType> From<&'a SigningKey<T>> for VerificationKey<T> {
     }
 }
 
+impl<T: SigType> VerificationKey<T> {
+    /// Verifies a purported `signature` over `msg` made by this verification key.
+    pub fn verify(&self, msg: &[u8], signature: &Signature<T>) -> Result<(), reddsa::Error> {
+        self.0.verify(msg, &signature.0)
+    }
+}
+
 impl<T: SigType> PartialEq for VerificationKey<T> {
     fn eq(&self, other: &Self) -> bool {
         <[u8; 32]>::from(self).eq(&<[u8; 32]>::from(other))
@@ -0,0 +1,1423 @@
+//! Threshold signing of `SpendAuth` signatures with FROST.
+//!
+//! This implements the two-round [FROST] threshold signature scheme as specified in
+//! [RFC 9591], with the FROST(Pallas, BLAKE2b-512) ciphersuite used by the `reddsa` and
+//! `frost-rerandomized` crates, and the rerandomization required by Orchard spend
+//! authorization signatures. A group of
+//! `max_signers` participants jointly control a spend authorizing key, and any
+//! `min_signers` of them can cooperate to create a `SpendAuth` [`Signature`] that verifies
+//! under the randomized key $\mathsf{rk} = \mathsf{ak} + [\alpha] \mathcal{G}$.
+//!
+//! Keys are created either by a trusted dealer with [`generate_with_dealer`], or without
+//! any trusted party with the distributed key generation in [`dkg`]. Either way, the
+//! group verification key always has a y-coordinate with sign 0, so that it can be used
+//! as the $\mathsf{ak}$ of an Orchard [`FullViewingKey`].
+//!
+//! Signing then proceeds as follows:
+//!
+//! 1. Each participant calls [`commit`], keeps the [`SigningNonces`] secret, and sends the
+//!    [`SigningCommitments`] to the coordinator.
+//! 2. The coordinator creates a [`SigningPackage`] from the commitments, the message
+//!    (the transaction sighash), and the randomizer $\alpha$ of the action being signed.
+//! 3. Each participant calls [`sign`] with the signing package and sends the resulting
+//!    [`SignatureShare`] to the coordinator.
+//! 4. The coordinator calls [`aggregate`] to obtain the signature, which can be added to
+//!    a bundle with `Bundle::append_signatures`.
+//!
+//! Nonces must never be reused: each [`SigningNonces`] is consumed by [`sign`].
+//!
+//! Every package that is sent between participants has a canonical encoding.
+//! [`SigningCommitments`], [`SignatureShare`] and [`dkg::Round2Package`] are encoded with
+//! `to_bytes` and `from_bytes`, and the other packages with `write` and `read`. As in
+//! [RFC 9591], decoding rejects non-canonical scalars, and group elements that are
+//! non-canonical or the identity.
+//!
+//! [FROST]: https://eprint.iacr.org/2020/852
+//! [RFC 9591]: https://www.rfc-editor.org/rfc/rfc9591.html
+//! [`FullViewingKey`]: crate::keys::FullViewingKey
+
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use blake2b_simd::{Params as Blake2bParams, State};
+use ff::Field;
+use group::{Group, GroupEncoding};
+use pasta_curves::{arithmetic::FieldExt, pallas};
+use rand::{CryptoRng, RngCore};
+
+use super::{Signature, SpendAuth, VerificationKey};
+use crate::{
+    constants::spend_auth_g,
+    serialization::{
+        invalid_data, read_bytes, read_compact_size_bounded, write_compact_size, MAX_COMPACT_SIZE,
+    },
+};
+
+/// The personalization for `H1`, the binding factor hash.
+const H1_PERSONALIZATION: &[u8; 16] = b"FROST_RedPallasR";
+
+/// The personalization for `H2`, the RedPallas challenge hash $H^\circledast$.
+const H2_PERSONALIZATION: &[u8; 16] = b"Zcash_RedPallasH";
+
+/// The personalization for `H3`, the nonce generation hash.
+const H3_PERSONALIZATION: &[u8; 16] = b"FROST_RedPallasN";
+
+/// The personalization for `H4`, the message hash.
+const H4_PERSONALIZATION: &[u8; 16] = b"FROST_RedPallasM";
+
+/// The personalization for `H5`, the commitment list hash.
+const H5_PERSONALIZATION: &[u8; 16] = b"FROST_RedPallasC";
+
+/// The personalization for `HDKG`, the DKG proof of knowledge challenge hash.
+const HDKG_PERSONALIZATION: &[u8; 16] = b"FROST_RedPallasD";
+
+/// Errors that can occur during FROST key generation and signing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The threshold must be at least 2, and no larger than the number of signers.
+    InvalidThreshold,
+    /// A participant identifier must be non-zero.
+    InvalidIdentifier,
+    /// The wrong number of packages was provided for this step.
+    IncorrectNumberOfPackages,
+    /// A package had a commitment of the wrong length.
+    IncorrectCommitmentLength(Identifier),
+    /// A participant's proof of knowledge of their secret was invalid.
+    InvalidProofOfKnowledge(Identifier),
+    /// A secret share did not match its sender's commitment.
+    InvalidSecretShare(Identifier),
+    /// The signing package does not include a commitment from this participant.
+    MissingCommitment(Identifier),
+    /// The signing package has fewer commitments than the signing threshold.
+    IncorrectNumberOfCommitments,
+    /// The signing package does not match the signature shares or nonces.
+    InvalidSigningPackage,
+    /// A participant's signature share was invalid.
+    InvalidSignatureShare(Identifier),
+    /// The aggregated signature was invalid.
+    InvalidSignature,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidThreshold => write!(f, "Invalid signing threshold"),
+            Error::InvalidIdentifier => write!(f, "Participant identifiers must be non-zero"),
+            Error::IncorrectNumberOfPackages => write!(f, "Incorrect number of packages"),
+            Error::IncorrectCommitmentLength(id) => {
+                write!(
+                    f,
+                    "Commitment from participant {} has the wrong length",
+                    id.0
+                )
+            }
+            Error::InvalidProofOfKnowledge(id) => {
+                write!(f, "Invalid proof of knowledge from participant {}", id.0)
+            }
+            Error::InvalidSecretShare(id) => {
+                write!(f, "Invalid secret share from participant {}", id.0)
+            }
+            Error::MissingCommitment(id) => {
+                write!(f, "Signing package is missing participant {}", id.0)
+            }
+            Error::IncorrectNumberOfCommitments => {
+                write!(
+                    f,
+                    "Signing package has fewer commitments than the threshold"
+                )
+            }
+            Error::InvalidSigningPackage => write!(f, "Invalid signing package"),
+            Error::InvalidSignatureShare(id) => {
+                write!(f, "Invalid signature share from participant {}", id.0)
+            }
+            Error::InvalidSignature => write!(f, "Aggregated signature is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The identifier of a participant in a FROST group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identifier(u16);
+
+impl TryFrom<u16> for Identifier {
+    type Error = Error;
+
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        if id == 0 {
+            Err(Error::InvalidIdentifier)
+        } else {
+            Ok(Identifier(id))
+        }
+    }
+}
+
+impl From<Identifier> for u16 {
+    fn from(id: Identifier) -> u16 {
+        id.0
+    }
+}
+
+impl Identifier {
+    fn to_scalar(self) -> pallas::Scalar {
+        pallas::Scalar::from_u64(self.0.into())
+    }
+
+    /// Serializes this identifier as a scalar, as required by the ciphersuite.
+    fn serialize(self) -> [u8; 32] {
+        self.to_scalar().to_bytes()
+    }
+}
+
+/// A participant's share of the group signing key.
+#[derive(Clone, Debug)]
+pub struct SecretShare {
+    identifier: Identifier,
+    signing_share: pallas::Scalar,
+    commitment: Vec<pallas::Point>,
+}
+
+impl SecretShare {
+    /// Returns the identifier of the participant that this share is for.
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Writes this secret share.
+    ///
+    /// The encoding is the identifier as two little-endian bytes, the signing share, and
+    /// the dealer's commitment. The share must only be sent to its participant over a
+    /// confidential and authenticated channel.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_identifier(&mut writer, self.identifier)?;
+        writer.write_all(&self.signing_share.to_bytes())?;
+        write_commitment(writer, &self.commitment)
+    }
+
+    /// Reads a secret share written by [`SecretShare::write`].
+    ///
+    /// The share is not checked against the dealer's commitment here; that happens when
+    /// it is converted into a [`KeyPackage`].
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        Ok(SecretShare {
+            identifier: read_identifier(&mut reader)?,
+            signing_share: read_scalar(&mut reader)?,
+            commitment: read_commitment(reader)?,
+        })
+    }
+}
+
+/// The key material that a participant uses to create signature shares.
+#[derive(Clone, Debug)]
+pub struct KeyPackage {
+    identifier: Identifier,
+    signing_share: pallas::Scalar,
+    verifying_share: pallas::Point,
+    group_public: pallas::Point,
+    min_signers: u16,
+}
+
+impl TryFrom<SecretShare> for KeyPackage {
+    type Error = Error;
+
+    /// Verifies a secret share from a trusted dealer against the dealer's commitment.
+    fn try_from(share: SecretShare) -> Result<Self, Self::Error> {
+        let verifying_share = generator() * share.signing_share;
+        if verifying_share != evaluate_vss(share.identifier, &share.commitment) {
+            return Err(Error::InvalidSecretShare(share.identifier));
+        }
+
+        Ok(KeyPackage {
+            identifier: share.identifier,
+            signing_share: share.signing_share,
+            verifying_share,
+            group_public: share.commitment[0],
+            min_signers: share.commitment.len() as u16,
+        })
+    }
+}
+
+impl KeyPackage {
+    /// Returns the identifier of this participant.
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Returns the group verification key.
+    pub fn group_public(&self) -> VerificationKey<SpendAuth> {
+        to_verification_key(&self.group_public)
+    }
+
+    /// Returns the number of participants needed to create a signature.
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    /// Writes this key package.
+    ///
+    /// The encoding is the identifier, the signing share, the verifying share, the group
+    /// verification key, and the threshold as two little-endian bytes.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_identifier(&mut writer, self.identifier)?;
+        writer.write_all(&self.signing_share.to_bytes())?;
+        writer.write_all(&self.verifying_share.to_bytes())?;
+        writer.write_all(&self.group_public.to_bytes())?;
+        writer.write_all(&self.min_signers.to_le_bytes())
+    }
+
+    /// Reads a key package written by [`KeyPackage::write`].
+    ///
+    /// Returns an error if the verifying share does not match the signing share, if the
+    /// group verification key cannot be used as $\mathsf{ak}$, or if the threshold is
+    /// less than 2.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let identifier = read_identifier(&mut reader)?;
+        let signing_share = read_scalar(&mut reader)?;
+        let verifying_share = read_point(&mut reader)?;
+        let group_public = read_group_public(&mut reader)?;
+        let min_signers = u16::from_le_bytes(read_bytes(&mut reader)?);
+
+        if verifying_share != generator() * signing_share {
+            return Err(invalid_data("verifying share does not match signing share"));
+        }
+        check_threshold(min_signers, u16::MAX).map_err(invalid_data)?;
+
+        Ok(KeyPackage {
+            identifier,
+            signing_share,
+            verifying_share,
+            group_public,
+            min_signers,
+        })
+    }
+}
+
+/// The public key material of a FROST group, used to aggregate signature shares.
+#[derive(Clone, Debug)]
+pub struct PublicKeyPackage {
+    verifying_shares: BTreeMap<Identifier, pallas::Point>,
+    group_public: pallas::Point,
+    min_signers: u16,
+}
+
+impl PublicKeyPackage {
+    /// Returns the group verification key.
+    ///
+    /// Its encoding can be used as the $\mathsf{ak}$ component of a full viewing key.
+    pub fn group_public(&self) -> VerificationKey<SpendAuth> {
+        to_verification_key(&self.group_public)
+    }
+
+    /// Returns the number of participants needed to create a signature.
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    /// Writes this public key package.
+    ///
+    /// The encoding is the verifying share of each participant in ascending order of
+    /// identifier, followed by the group verification key and the threshold as two
+    /// little-endian bytes.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_map(&mut writer, &self.verifying_shares, |share| {
+            share.to_bytes()
+        })?;
+        writer.write_all(&self.group_public.to_bytes())?;
+        writer.write_all(&self.min_signers.to_le_bytes())
+    }
+
+    /// Reads a public key package written by [`PublicKeyPackage::write`].
+    ///
+    /// Returns an error if the group verification key cannot be used as
+    /// $\mathsf{ak}$, or if the threshold is not valid for the number of participants.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let verifying_shares = read_map(
+            &mut reader,
+            |bytes| point_from_bytes(&bytes),
+            "invalid verifying share",
+        )?;
+        let group_public = read_group_public(&mut reader)?;
+        let min_signers = u16::from_le_bytes(read_bytes(&mut reader)?);
+
+        // The map has at most u16::MAX entries.
+        check_threshold(min_signers, verifying_shares.len() as u16).map_err(invalid_data)?;
+
+        Ok(PublicKeyPackage {
+            verifying_shares,
+            group_public,
+            min_signers,
+        })
+    }
+}
+
+/// Checks that `min_signers` and `max_signers` describe a valid threshold.
+fn check_threshold(min_signers: u16, max_signers: u16) -> Result<(), Error> {
+    if (2..=max_signers).contains(&min_signers) {
+        Ok(())
+    } else {
+        Err(Error::InvalidThreshold)
+    }
+}
+
+/// Generates key shares for `max_signers` participants with a trusted dealer, such that
+/// any `min_signers` of them can sign.
+///
+/// The participants are given the identifiers `1..=max_signers`. Each participant
+/// should check their [`SecretShare`] by converting it into a [`KeyPackage`].
+pub fn generate_with_dealer<R: RngCore + CryptoRng>(
+    max_signers: u16,
+    min_signers: u16,
+    mut rng: R,
+) -> Result<(BTreeMap<Identifier, SecretShare>, PublicKeyPackage), Error> {
+    check_threshold(min_signers, max_signers)?;
+
+    let mut coefficients: Vec<_> = (0..min_signers)
+        .map(|_| pallas::Scalar::random(&mut rng))
+        .collect();
+    // The group key must be usable as ak, so its y-coordinate must have sign 0.
+    if is_odd(&(generator() * coefficients[0])) {
+        coefficients[0] = -coefficients[0];
+    }
+    let commitment: Vec<_> = coefficients.iter().map(|a| generator() * a).collect();
+
+    let mut shares = BTreeMap::new();
+    let mut verifying_shares = BTreeMap::new();
+    for id in (1..=max_signers).map(Identifier) {
+        let signing_share = evaluate_polynomial(id, &coefficients);
+        verifying_shares.insert(id, generator() * signing_share);
+        shares.insert(
+            id,
+            SecretShare {
+                identifier: id,
+                signing_share,
+                commitment: commitment.clone(),
+            },
+        );
+    }
+
+    Ok((
+        shares,
+        PublicKeyPackage {
+            verifying_shares,
+            group_public: commitment[0],
+            min_signers,
+        },
+    ))
+}
+
+/// Distributed key generation, following the Pedersen DKG with proofs of knowledge from
+/// the FROST paper.
+///
+/// Each participant calls [`part1`], broadcasts its [`Round1Package`] to the other
+/// participants, then calls [`part2`] and sends each [`Round2Package`] privately to its
+/// recipient, and finally calls [`part3`] to obtain its key material.
+pub mod dkg {
+    use std::collections::BTreeMap;
+    use std::io::{self, Read, Write};
+
+    use ff::Field;
+    use group::{Group, GroupEncoding};
+    use pasta_curves::{arithmetic::FieldExt, pallas};
+    use rand::{CryptoRng, RngCore};
+
+    use super::{
+        check_threshold, dkg_challenge, evaluate_polynomial, evaluate_vss, generator, is_odd,
+        read_commitment, read_point, read_scalar, scalar_from_bytes, write_commitment, Error,
+        Identifier, KeyPackage, PublicKeyPackage,
+    };
+
+    /// The secret state of a participant after the first round of the DKG.
+    #[derive(Debug)]
+    pub struct Round1SecretPackage {
+        identifier: Identifier,
+        coefficients: Vec<pallas::Scalar>,
+        commitment: Vec<pallas::Point>,
+        max_signers: u16,
+    }
+
+    /// The package that a participant broadcasts in the first round of the DKG.
+    #[derive(Clone, Debug)]
+    pub struct Round1Package {
+        commitment: Vec<pallas::Point>,
+        proof_of_knowledge: (pallas::Point, pallas::Scalar),
+    }
+
+    /// The secret state of a participant after the second round of the DKG.
+    #[derive(Debug)]
+    pub struct Round2SecretPackage {
+        identifier: Identifier,
+        own_share: pallas::Scalar,
+        commitment: Vec<pallas::Point>,
+        max_signers: u16,
+    }
+
+    impl Round1Package {
+        /// Writes this package.
+        ///
+        /// The encoding is the participant's commitment, followed by the point and the
+        /// scalar of its proof of knowledge. The sender is not included.
+        pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+            write_commitment(&mut writer, &self.commitment)?;
+            writer.write_all(&self.proof_of_knowledge.0.to_bytes())?;
+            writer.write_all(&self.proof_of_knowledge.1.to_bytes())
+        }
+
+        /// Reads a package written by [`Round1Package::write`].
+        ///
+        /// The proof of knowledge is checked by [`part2`] and [`part3`].
+        pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+            Ok(Round1Package {
+                commitment: read_commitment(&mut reader)?,
+                proof_of_knowledge: (read_point(&mut reader)?, read_scalar(reader)?),
+            })
+        }
+    }
+
+    /// The package that a participant sends privately to each other participant in the
+    /// second round of the DKG.
+    #[derive(Clone, Debug)]
+    pub struct Round2Package {
+        signing_share: pallas::Scalar,
+    }
+
+    impl Round2Package {
+        /// Returns the encoding of this package, which is the share of the sender's secret
+        /// for the recipient. It must be sent over a confidential and authenticated
+        /// channel.
+        pub fn to_bytes(&self) -> [u8; 32] {
+            self.signing_share.to_bytes()
+        }
+
+        /// Parses a package from its encoding, returning `None` if the share is not a
+        /// canonically encoded scalar.
+        pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+            scalar_from_bytes(bytes).map(|signing_share| Round2Package { signing_share })
+        }
+    }
+
+    /// Performs the first round of the DKG for the participant `identifier`.
+    pub fn part1<R: RngCore + CryptoRng>(
+        identifier: Identifier,
+        max_signers: u16,
+        min_signers: u16,
+        mut rng: R,
+    ) -> Result<(Round1SecretPackage, Round1Package), Error> {
+        check_threshold(min_signers, max_signers)?;
+
+        let coefficients: Vec<_> = (0..min_signers)
+            .map(|_| pallas::Scalar::random(&mut rng))
+            .collect();
+        let commitment: Vec<_> = coefficients.iter().map(|a| generator() * a).collect();
+
+        // Prove knowledge of the constant term, to prevent rogue-key attacks.
+        let k = pallas::Scalar::random(&mut rng);
+        let r = generator() * k;
+        let c = dkg_challenge(identifier, &commitment[0], &r);
+        let mu = k + coefficients[0] * c;
+
+        Ok((
+            Round1SecretPackage {
+                identifier,
+                coefficients,
+                commitment: commitment.clone(),
+                max_signers,
+            },
+            Round1Package {
+                commitment,
+                proof_of_knowledge: (r, mu),
+            },
+        ))
+    }
+
+    /// Checks the first-round packages from the other participants.
+    fn check_round1_packages(
+        identifier: Identifier,
+        commitment_len: usize,
+        max_signers: u16,
+        round1_packages: &BTreeMap<Identifier, Round1Package>,
+    ) -> Result<(), Error> {
+        if round1_packages.len() != usize::from(max_signers) - 1
+            || round1_packages.contains_key(&identifier)
+        {
+            return Err(Error::IncorrectNumberOfPackages);
+        }
+
+        for (&sender, package) in round1_packages {
+            if package.commitment.len() != commitment_len {
+                return Err(Error::IncorrectCommitmentLength(sender));
+            }
+            let (r, mu) = package.proof_of_knowledge;
+            let c = dkg_challenge(sender, &package.commitment[0], &r);
+            if generator() * mu != r + package.commitment[0] * c {
+                return Err(Error::InvalidProofOfKnowledge(sender));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Performs the second round of the DKG, given the first-round packages broadcast by
+    /// every other participant.
+    ///
+    /// Returns the packages to send to each other participant.
+    pub fn part2(
+        secret_package: Round1SecretPackage,
+        round1_packages: &BTreeMap<Identifier, Round1Package>,
+    ) -> Result<(Round2SecretPackage, BTreeMap<Identifier, Round2Package>), Error> {
+        check_round1_packages(
+            secret_package.identifier,
+            secret_package.commitment.len(),
+            secret_package.max_signers,
+            round1_packages,
+        )?;
+
+        let round2_packages = round1_packages
+            .keys()
+            .map(|&recipient| {
+                (
+                    recipient,
+                    Round2Package {
+                        signing_share: evaluate_polynomial(recipient, &secret_package.coefficients),
+                    },
+                )
+            })
+            .collect();
+
+        Ok((
+            Round2SecretPackage {
+                identifier: secret_package.identifier,
+                own_share: evaluate_polynomial(
+                    secret_package.identifier,
+                    &secret_package.coefficients,
+                ),
+                commitment: secret_package.commitment,
+                max_signers: secret_package.max_signers,
+            },
+            round2_packages,
+        ))
+    }
+
+    /// Performs the final step of the DKG, given the first-round packages broadcast by
+    /// every other participant and the second-round packages sent to this participant.
+    pub fn part3(
+        secret_package: &Round2SecretPackage,
+        round1_packages: &BTreeMap<Identifier, Round1Package>,
+        round2_packages: &BTreeMap<Identifier, Round2Package>,
+    ) -> Result<(KeyPackage, PublicKeyPackage), Error> {
+        let identifier = secret_package.identifier;
+        check_round1_packages(
+            identifier,
+            secret_package.commitment.len(),
+            secret_package.max_signers,
+            round1_packages,
+        )?;
+        if round2_packages.len() != round1_packages.len()
+            || round2_packages
+                .keys()
+                .any(|sender| !round1_packages.contains_key(sender))
+        {
+            return Err(Error::IncorrectNumberOfPackages);
+        }
+
+        let mut signing_share = secret_package.own_share;
+        for (sender, package) in round2_packages {
+            let commitment = &round1_packages[sender].commitment;
+            if generator() * package.signing_share != evaluate_vss(identifier, commitment) {
+                return Err(Error::InvalidSecretShare(*sender));
+            }
+            signing_share += package.signing_share;
+        }
+
+        // The group's commitment is the sum of every participant's commitment.
+        let mut group_commitment = secret_package.commitment.clone();
+        for package in round1_packages.values() {
+            for (acc, c) in group_commitment.iter_mut().zip(package.commitment.iter()) {
+                *acc += c;
+            }
+        }
+
+        let min_signers = group_commitment.len() as u16;
+        let mut group_public = group_commitment[0];
+        let mut verifying_shares: BTreeMap<_, _> = round1_packages
+            .keys()
+            .chain(Some(&identifier))
+            .map(|&id| (id, evaluate_vss(id, &group_commitment)))
+            .collect();
+
+        // The group key must be usable as ak, so its y-coordinate must have sign 0. Every
+        // participant negates their share in the same case, so this is consistent.
+        if is_odd(&group_public) {
+            signing_share = -signing_share;
+            group_public = -group_public;
+            for share in verifying_shares.values_mut() {
+                *share = -*share;
+            }
+        }
+        debug_assert!(!bool::from(group_public.is_identity()));
+
+        Ok((
+            KeyPackage {
+                identifier,
+                signing_share,
+                verifying_share: verifying_shares[&identifier],
+                group_public,
+                min_signers,
+            },
+            PublicKeyPackage {
+                verifying_shares,
+                group_public,
+                min_signers,
+            },
+        ))
+    }
+}
+
+/// The secret nonces created by a participant in the first round of signing.
+///
+/// These must be used for at most one signature.
+#[derive(Debug)]
+pub struct SigningNonces {
+    hiding: pallas::Scalar,
+    binding: pallas::Scalar,
+}
+
+/// The commitments to a participant's [`SigningNonces`], which are sent to the
+/// coordinator.
+#[derive(Clone, Copy, Debug)]
+pub struct SigningCommitments {
+    hiding: pallas::Point,
+    binding: pallas::Point,
+}
+
+impl SigningCommitments {
+    /// Returns the encoding of these commitments, which is the hiding commitment followed
+    /// by the binding commitment.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&self.hiding.to_bytes());
+        bytes[32..].copy_from_slice(&self.binding.to_bytes());
+        bytes
+    }
+
+    /// Parses commitments from their encoding, returning `None` if either commitment is
+    /// not a canonically encoded non-identity point.
+    pub fn from_bytes(bytes: &[u8; 64]) -> Option<Self> {
+        Some(SigningCommitments {
+            hiding: point_from_bytes(bytes[..32].try_into().unwrap())?,
+            binding: point_from_bytes(bytes[32..].try_into().unwrap())?,
+        })
+    }
+}
+
+/// Performs the first round of signing, creating nonces and the commitments to them.
+///
+/// The nonces are derived from both fresh randomness and the participant's signing share,
+/// so that a weak random number generator does not immediately leak the share.
+pub fn commit<R: RngCore + CryptoRng>(
+    key_package: &KeyPackage,
+    mut rng: R,
+) -> (SigningNonces, SigningCommitments) {
+    let nonces = SigningNonces {
+        hiding: nonce_generate(&key_package.signing_share, &mut rng),
+        binding: nonce_generate(&key_package.signing_share, &mut rng),
+    };
+    let commitments = SigningCommitments {
+        hiding: generator() * nonces.hiding,
+        binding: generator() * nonces.binding,
+    };
+    (nonces, commitments)
+}
+
+/// The data that the coordinator sends to each participant in the second round of
+/// signing.
+#[derive(Clone, Debug)]
+pub struct SigningPackage {
+    commitments: BTreeMap<Identifier, SigningCommitments>,
+    message: Vec<u8>,
+    randomizer: pallas::Scalar,
+}
+
+impl SigningPackage {
+    /// Creates a signing package for a signature on `message` under the group key
+    /// randomized by `randomizer`.
+    ///
+    /// For a spend authorization signature, `message` is the transaction sighash and
+    /// `randomizer` is the $\alpha$ of the action being signed.
+    pub fn new(
+        commitments: BTreeMap<Identifier, SigningCommitments>,
+        message: &[u8],
+        randomizer: pallas::Scalar,
+    ) -> Self {
+        SigningPackage {
+            commitments,
+            message: message.to_vec(),
+            randomizer,
+        }
+    }
+
+    /// Writes this signing package.
+    ///
+    /// The encoding is the commitments of each participant in ascending order of
+    /// identifier, the length-prefixed message, and the randomizer.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_map(&mut writer, &self.commitments, |commitments| {
+            commitments.to_bytes()
+        })?;
+        write_compact_size(&mut writer, self.message.len() as u64)?;
+        writer.write_all(&self.message)?;
+        writer.write_all(&self.randomizer.to_bytes())
+    }
+
+    /// Reads a signing package written by [`SigningPackage::write`].
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let commitments = read_map(
+            &mut reader,
+            |bytes| SigningCommitments::from_bytes(&bytes),
+            "invalid signing commitments",
+        )?;
+
+        let message_len = read_compact_size_bounded(&mut reader, MAX_COMPACT_SIZE)?;
+        let mut message = vec![];
+        (&mut reader)
+            .take(message_len as u64)
+            .read_to_end(&mut message)?;
+        if message.len() != message_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "message is truncated",
+            ));
+        }
+
+        Ok(SigningPackage {
+            commitments,
+            message,
+            randomizer: read_scalar(reader)?,
+        })
+    }
+
+    /// Returns the randomized group verification key that the signature will verify
+    /// under.
+    fn randomized_group_public(&self, group_public: &pallas::Point) -> pallas::Point {
+        group_public + generator() * self.randomizer
+    }
+
+    /// Computes the binding factor of each participant, and the group commitment.
+    ///
+    /// The binding factors are bound to the randomized group key `rk`, so that a
+    /// signature share for one randomizer cannot be reused for another.
+    fn binding_factors(
+        &self,
+        rk: &pallas::Point,
+    ) -> (BTreeMap<Identifier, pallas::Scalar>, pallas::Point) {
+        let mut encoded_commitments = hasher(H5_PERSONALIZATION);
+        for (id, commitments) in &self.commitments {
+            encoded_commitments.update(&id.serialize());
+            encoded_commitments.update(&commitments.hiding.to_bytes());
+            encoded_commitments.update(&commitments.binding.to_bytes());
+        }
+
+        let mut prefix = hasher(H1_PERSONALIZATION);
+        prefix.update(&rk.to_bytes());
+        prefix.update(
+            hasher(H4_PERSONALIZATION)
+                .update(&self.message)
+                .finalize()
+                .as_bytes(),
+        );
+        prefix.update(encoded_commitments.finalize().as_bytes());
+
+        let binding_factors: BTreeMap<_, _> = self
+            .commitments
+            .keys()
+            .map(|id| {
+                let mut h: State = prefix.clone();
+                h.update(&id.serialize());
+                (*id, to_scalar(h))
+            })
+            .collect();
+
+        let group_commitment = self
+            .commitments
+            .iter()
+            .map(|(id, c)| c.hiding + c.binding * binding_factors[id])
+            .fold(pallas::Point::identity(), |acc, r| acc + r);
+
+        (binding_factors, group_commitment)
+    }
+
+    /// Returns the Lagrange coefficient of the given participant at zero.
+    fn lagrange_coefficient(&self, identifier: Identifier) -> pallas::Scalar {
+        let x_i = identifier.to_scalar();
+        self.commitments
+            .keys()
+            .filter(|&&id| id != identifier)
+            .fold(pallas::Scalar::one(), |acc, id| {
+                let x_j = id.to_scalar();
+                // Identifiers are distinct, so x_j - x_i is non-zero.
+                acc * x_j * (x_j - x_i).invert().unwrap()
+            })
+    }
+}
+
+/// A participant's share of a signature.
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureShare {
+    share: pallas::Scalar,
+}
+
+impl SignatureShare {
+    /// Returns the encoding of this signature share.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.share.to_bytes()
+    }
+
+    /// Parses a signature share from its encoding, returning `None` if it is not a
+    /// canonically encoded scalar.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        scalar_from_bytes(bytes).map(|share| SignatureShare { share })
+    }
+}
+
+/// Performs the second round of signing, creating this participant's signature share.
+///
+/// The nonces are consumed, so that they cannot be reused for another signature.
+pub fn sign(
+    signing_package: &SigningPackage,
+    nonces: SigningNonces,
+    key_package: &KeyPackage,
+) -> Result<SignatureShare, Error> {
+    if signing_package.commitments.len() < usize::from(key_package.min_signers) {
+        return Err(Error::IncorrectNumberOfCommitments);
+    }
+
+    let identifier = key_package.identifier;
+    let commitments = signing_package
+        .commitments
+        .get(&identifier)
+        .ok_or(Error::MissingCommitment(identifier))?;
+    if commitments.hiding != generator() * nonces.hiding
+        || commitments.binding != generator() * nonces.binding
+    {
+        return Err(Error::InvalidSigningPackage);
+    }
+
+    let rk = signing_package.randomized_group_public(&key_package.group_public);
+    let (binding_factors, group_commitment) = signing_package.binding_factors(&rk);
+    let c = challenge(&group_commitment, &rk, &signing_package.message);
+    let lambda = signing_package.lagrange_coefficient(identifier);
+
+    Ok(SignatureShare {
+        share: nonces.hiding
+            + nonces.binding * binding_factors[&identifier]
+            + lambda * key_package.signing_share * c,
+    })
+}
+
+/// Aggregates the signature shares of the participants into a spend authorization
+/// signature.
+///
+/// If the aggregated signature is invalid, each share is checked so that a misbehaving
+/// participant can be identified.
+pub fn aggregate(
+    signing_package: &SigningPackage,
+    signature_shares: &BTreeMap<Identifier, SignatureShare>,
+    public_key_package: &PublicKeyPackage,
+) -> Result<Signature<SpendAuth>, Error> {
+    if signing_package.commitments.len() < usize::from(public_key_package.min_signers) {
+        return Err(Error::IncorrectNumberOfCommitments);
+    }
+    if signature_shares.len() != signing_package.commitments.len()
+        || signature_shares
+            .keys()
+            .any(|id| !signing_package.commitments.contains_key(id))
+    {
+        return Err(Error::InvalidSigningPackage);
+    }
+
+    let rk = signing_package.randomized_group_public(&public_key_package.group_public);
+    let (binding_factors, group_commitment) = signing_package.binding_factors(&rk);
+    let c = challenge(&group_commitment, &rk, &signing_package.message);
+
+    // The randomizer is applied to the sum of the shares, not to any individual share.
+    let z = signature_shares
+        .values()
+        .fold(signing_package.randomizer * c, |acc, s| acc + s.share);
+
+    let mut bytes = [0; 64];
+    bytes[..32].copy_from_slice(&group_commitment.to_bytes());
+    bytes[32..].copy_from_slice(&z.to_bytes());
+    let signature = Signature::from(bytes);
+
+    if to_verification_key(&rk)
+        .verify(&signing_package.message, &signature)
+        .is_ok()
+    {
+        return Ok(signature);
+    }
+
+    // Find the participant responsible for the invalid signature.
+    for (id, share) in signature_shares {
+        let commitments = &signing_package.commitments[id];
+        let verifying_share = public_key_package
+            .verifying_shares
+            .get(id)
+            .ok_or(Error::InvalidSignatureShare(*id))?;
+        let lambda = signing_package.lagrange_coefficient(*id);
+        if generator() * share.share
+            != commitments.hiding
+                + commitments.binding * binding_factors[id]
+                + verifying_share * (lambda * c)
+        {
+            return Err(Error::InvalidSignatureShare(*id));
+        }
+    }
+
+    Err(Error::InvalidSignature)
+}
+
+/// The generator $\mathcal{G}^\mathsf{Orchard}$ used for spend authorization signatures.
+fn generator() -> pallas::Point {
+    spend_auth_g::generator().into()
+}
+
+/// Returns whether the encoding of the given point has its sign bit set.
+fn is_odd(point: &pallas::Point) -> bool {
+    point.to_bytes()[31] >> 7 == 1
+}
+
+fn to_verification_key(point: &pallas::Point) -> VerificationKey<SpendAuth> {
+    VerificationKey::try_from(point.to_bytes()).expect("valid point encoding")
+}
+
+/// Returns a BLAKE2b-512 state with the given personalization.
+fn hasher(personalization: &[u8; 16]) -> State {
+    Blake2bParams::new()
+        .hash_length(64)
+        .personal(personalization)
+        .to_state()
+}
+
+/// Reduces a 64-byte hash output to a scalar.
+fn to_scalar(h: State) -> pallas::Scalar {
+    pallas::Scalar::from_bytes_wide(h.finalize().as_bytes().try_into().unwrap())
+}
+
+/// Evaluates the polynomial with the given coefficients at `identifier`.
+fn evaluate_polynomial(identifier: Identifier, coefficients: &[pallas::Scalar]) -> pallas::Scalar {
+    let x = identifier.to_scalar();
+    coefficients
+        .iter()
+        .rev()
+        .fold(pallas::Scalar::zero(), |acc, a| acc * x + a)
+}
+
+/// Evaluates a commitment to a polynomial at `identifier`.
+fn evaluate_vss(identifier: Identifier, commitment: &[pallas::Point]) -> pallas::Point {
+    let x = identifier.to_scalar();
+    commitment
+        .iter()
+        .rev()
+        .fold(pallas::Point::identity(), |acc, c| acc * x + c)
+}
+
+/// Generates a nonce from fresh randomness and the participant's signing share.
+fn nonce_generate<R: RngCore + CryptoRng>(secret: &pallas::Scalar, mut rng: R) -> pallas::Scalar {
+    let mut random_bytes = [0; 32];
+    rng.fill_bytes(&mut random_bytes);
+    let mut h = hasher(H3_PERSONALIZATION);
+    h.update(&random_bytes);
+    h.update(&secret.to_bytes());
+    to_scalar(h)
+}
+
+/// The RedPallas challenge $H^\circledast(R \| \mathsf{vk} \| M)$.
+fn challenge(r: &pallas::Point, vk: &pallas::Point, message: &[u8]) -> pallas::Scalar {
+    let mut h = hasher(H2_PERSONALIZATION);
+    h.update(&r.to_bytes());
+    h.update(&vk.to_bytes());
+    h.update(message);
+    to_scalar(h)
+}
+
+/// The challenge for a DKG proof of knowledge.
+fn dkg_challenge(
+    identifier: Identifier,
+    commitment: &pallas::Point,
+    r: &pallas::Point,
+) -> pallas::Scalar {
+    let mut h = hasher(HDKG_PERSONALIZATION);
+    h.update(&identifier.serialize());
+    h.update(&commitment.to_bytes());
+    h.update(&r.to_bytes());
+    to_scalar(h)
+}
+
+/// Writes an identifier as two little-endian bytes.
+fn write_identifier<W: Write>(mut writer: W, identifier: Identifier) -> io::Result<()> {
+    writer.write_all(&identifier.0.to_le_bytes())
+}
+
+/// Reads an identifier written by [`write_identifier`].
+fn read_identifier<R: Read>(reader: R) -> io::Result<Identifier> {
+    Identifier::try_from(u16::from_le_bytes(read_bytes(reader)?)).map_err(invalid_data)
+}
+
+/// Parses a canonically encoded scalar.
+fn scalar_from_bytes(bytes: &[u8; 32]) -> Option<pallas::Scalar> {
+    pallas::Scalar::from_bytes(bytes).into()
+}
+
+/// Parses a canonically encoded point, rejecting the identity.
+fn point_from_bytes(bytes: &[u8; 32]) -> Option<pallas::Point> {
+    Option::<pallas::Point>::from(pallas::Point::from_bytes(bytes))
+        .filter(|point| !bool::from(point.is_identity()))
+}
+
+fn read_scalar<R: Read>(reader: R) -> io::Result<pallas::Scalar> {
+    scalar_from_bytes(&read_bytes(reader)?).ok_or_else(|| invalid_data("non-canonical scalar"))
+}
+
+fn read_point<R: Read>(reader: R) -> io::Result<pallas::Point> {
+    point_from_bytes(&read_bytes(reader)?).ok_or_else(|| invalid_data("invalid group element"))
+}
+
+/// Reads a group verification key, which must be usable as $\mathsf{ak}$.
+fn read_group_public<R: Read>(reader: R) -> io::Result<pallas::Point> {
+    let group_public = read_point(reader)?;
+    if is_odd(&group_public) {
+        Err(invalid_data(
+            "group verification key has an odd y-coordinate",
+        ))
+    } else {
+        Ok(group_public)
+    }
+}
+
+/// Writes a commitment to a polynomial, prefixed by its length as a compact size.
+fn write_commitment<W: Write>(mut writer: W, commitment: &[pallas::Point]) -> io::Result<()> {
+    write_compact_size(&mut writer, commitment.len() as u64)?;
+    for c in commitment {
+        writer.write_all(&c.to_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads a commitment written by [`write_commitment`], which must be long enough for a
+/// valid threshold.
+fn read_commitment<R: Read>(mut reader: R) -> io::Result<Vec<pallas::Point>> {
+    let len = read_compact_size_bounded(&mut reader, u16::MAX.into())?;
+    if len < 2 {
+        return Err(invalid_data("commitment is too short"));
+    }
+    (0..len).map(|_| read_point(&mut reader)).collect()
+}
+
+/// Writes a map from participants to values in ascending order of identifier, prefixed
+/// by its length as a compact size.
+fn write_map<W: Write, T, B: AsRef<[u8]>>(
+    mut writer: W,
+    map: &BTreeMap<Identifier, T>,
+    to_bytes: impl Fn(&T) -> B,
+) -> io::Result<()> {
+    write_compact_size(&mut writer, map.len() as u64)?;
+    for (&id, value) in map {
+        write_identifier(&mut writer, id)?;
+        writer.write_all(to_bytes(value).as_ref())?;
+    }
+    Ok(())
+}
+
+/// Reads a map written by [`write_map`].
+///
+/// Identifiers that are repeated or out of order are rejected, so that the encoding is
+/// canonical.
+fn read_map<R: Read, T, const N: usize>(
+    mut reader: R,
+    from_bytes: impl Fn([u8; N]) -> Option<T>,
+    error: &'static str,
+) -> io::Result<BTreeMap<Identifier, T>> {
+    let len = read_compact_size_bounded(&mut reader, u16::MAX.into())?;
+    let mut map = BTreeMap::new();
+    for _ in 0..len {
+        let id = read_identifier(&mut reader)?;
+        if map.keys().next_back().map_or(false, |&last| last >= id) {
+            return Err(invalid_data("identifiers are not in ascending order"));
+        }
+        let value = from_bytes(read_bytes(&mut reader)?).ok_or_else(|| invalid_data(error))?;
+        map.insert(id, value);
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::convert::{TryFrom, TryInto};
+    use std::io;
+
+    use ff::Field;
+    use group::GroupEncoding;
+    use pasta_curves::pallas;
+    use rand::rngs::OsRng;
+
+    use super::{
+        aggregate, commit, dkg, generate_with_dealer, sign, Error, Identifier, KeyPackage,
+        PublicKeyPackage, SecretShare, SignatureShare, SigningCommitments, SigningPackage,
+    };
+
+    /// Encodes a package with `write` and decodes it with `read`, as if it were sent to
+    /// another participant, and checks that the encoding is canonical.
+    fn round_trip<T>(
+        value: &T,
+        write: impl Fn(&T, &mut Vec<u8>) -> io::Result<()>,
+        read: impl Fn(&[u8]) -> io::Result<T>,
+    ) -> T {
+        let mut bytes = vec![];
+        write(value, &mut bytes).unwrap();
+        let decoded = read(&bytes).unwrap();
+        let mut encoded = vec![];
+        write(&decoded, &mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+        decoded
+    }
+
+    /// Signs `message` with the given participants, returning the signature shares.
+    fn sign_with(
+        key_packages: &BTreeMap<Identifier, KeyPackage>,
+        signers: &[u16],
+        message: &[u8],
+        randomizer: pallas::Scalar,
+    ) -> (SigningPackage, BTreeMap<Identifier, SignatureShare>) {
+        let signers: Vec<_> = signers
+            .iter()
+            .map(|&id| Identifier::try_from(id).unwrap())
+            .collect();
+        let (nonces, commitments): (BTreeMap<_, _>, BTreeMap<_, _>) = signers
+            .iter()
+            .map(|&id| {
+                let (nonces, commitments) = commit(&key_packages[&id], OsRng);
+                let commitments = SigningCommitments::from_bytes(&commitments.to_bytes()).unwrap();
+                ((id, nonces), (id, commitments))
+            })
+            .unzip();
+
+        let signing_package = SigningPackage::new(commitments, message, randomizer);
+        let received = round_trip(
+            &signing_package,
+            |p, w| p.write(w),
+            |b| SigningPackage::read(b),
+        );
+        let shares = nonces
+            .into_iter()
+            .map(|(id, nonces)| {
+                let share = sign(&received, nonces, &key_packages[&id]).unwrap();
+                (id, SignatureShare::from_bytes(&share.to_bytes()).unwrap())
+            })
+            .collect();
+        (signing_package, shares)
+    }
+
+    fn check_signing(
+        key_packages: &BTreeMap<Identifier, KeyPackage>,
+        public_key_package: &PublicKeyPackage,
+    ) {
+        // The group key can be used as ak.
+        let group_public = public_key_package.group_public();
+        assert_eq!(<[u8; 32]>::from(&group_public)[31] >> 7, 0);
+
+        let message = [42; 32];
+        let alpha = pallas::Scalar::random(OsRng);
+        let rk = group_public.randomize(&alpha);
+
+        for signers in &[&[1, 2][..], &[1, 3], &[2, 3], &[1, 2, 3]] {
+            let (signing_package, shares) = sign_with(key_packages, signers, &message, alpha);
+            let signature = aggregate(&signing_package, &shares, public_key_package).unwrap();
+            assert!(rk.verify(&message, &signature).is_ok());
+            assert!(group_public.verify(&message, &signature).is_err());
+        }
+
+        // A single signer is below the threshold.
+        let id = Identifier::try_from(1).unwrap();
+        let (nonces, commitments) = commit(&key_packages[&id], OsRng);
+        let signing_package = SigningPackage::new(
+            Some((id, commitments)).into_iter().collect(),
+            &message,
+            alpha,
+        );
+        assert_eq!(key_packages[&id].min_signers(), 2);
+        assert_eq!(
+            sign(&signing_package, nonces, &key_packages[&id]).unwrap_err(),
+            Error::IncorrectNumberOfCommitments
+        );
+        assert_eq!(
+            aggregate(&signing_package, &BTreeMap::new(), public_key_package).unwrap_err(),
+            Error::IncorrectNumberOfCommitments
+        );
+
+        // An invalid signature share is attributed to its sender.
+        let (signing_package, mut shares) = sign_with(key_packages, &[1, 3], &message, alpha);
+        let id = Identifier::try_from(3).unwrap();
+        shares.get_mut(&id).unwrap().share += pallas::Scalar::one();
+        assert_eq!(
+            aggregate(&signing_package, &shares, public_key_package).unwrap_err(),
+            Error::InvalidSignatureShare(id)
+        );
+    }
+
+    #[test]
+    fn dealer_keygen_and_signing() {
+        assert_eq!(
+            generate_with_dealer(3, 1, OsRng).unwrap_err(),
+            Error::InvalidThreshold
+        );
+        assert_eq!(
+            generate_with_dealer(2, 3, OsRng).unwrap_err(),
+            Error::InvalidThreshold
+        );
+
+        let (shares, public_key_package) = generate_with_dealer(3, 2, OsRng).unwrap();
+        let key_packages: BTreeMap<_, _> = shares
+            .into_iter()
+            .map(|(id, share)| {
+                let share = round_trip(&share, |s, w| s.write(w), |b| SecretShare::read(b));
+                let key_package: KeyPackage = share.try_into().unwrap();
+                (
+                    id,
+                    round_trip(&key_package, |k, w| k.write(w), |b| KeyPackage::read(b)),
+                )
+            })
+            .collect();
+        let public_key_package = round_trip(
+            &public_key_package,
+            |p, w| p.write(w),
+            |b| PublicKeyPackage::read(b),
+        );
+        check_signing(&key_packages, &public_key_package);
+
+        // Tampered shares are detected.
+        let (shares, _) = generate_with_dealer(3, 2, OsRng).unwrap();
+        let mut share = shares.into_iter().next().unwrap().1;
+        share.signing_share += pallas::Scalar::one();
+        let id = share.identifier();
+        assert_eq!(
+            KeyPackage::try_from(share).unwrap_err(),
+            Error::InvalidSecretShare(id)
+        );
+    }
+
+    #[test]
+    fn dkg_and_signing() {
+        let ids: Vec<_> = (1..=3)
+            .map(|id| Identifier::try_from(id).unwrap())
+            .collect();
+
+        let mut round1_secrets = BTreeMap::new();
+        let mut round1_packages = BTreeMap::new();
+        for &id in &ids {
+            let (secret, package) = dkg::part1(id, 3, 2, OsRng).unwrap();
+            round1_secrets.insert(id, secret);
+            round1_packages.insert(
+                id,
+                round_trip(&package, |p, w| p.write(w), |b| dkg::Round1Package::read(b)),
+            );
+        }
+        let others = |id: Identifier| -> BTreeMap<_, _> {
+            round1_packages
+                .iter()
+                .filter(|(&other, _)| other != id)
+                .map(|(&other, package)| (other, package.clone()))
+                .collect()
+        };
+
+        let mut round2_secrets = BTreeMap::new();
+        let mut received: BTreeMap<Identifier, BTreeMap<_, _>> = BTreeMap::new();
+        for (id, secret) in round1_secrets {
+            let (secret, packages) = dkg::part2(secret, &others(id)).unwrap();
+            round2_secrets.insert(id, secret);
+            for (recipient, package) in packages {
+                let package = dkg::Round2Package::from_bytes(&package.to_bytes()).unwrap();
+                received.entry(recipient).or_default().insert(id, package);
+            }
+        }
+
+        let mut key_packages = BTreeMap::new();
+        let mut public_key_package = None;
+        for &id in &ids {
+            let (key_package, public) =
+                dkg::part3(&round2_secrets[&id], &others(id), &received[&id]).unwrap();
+            key_packages.insert(
+                id,
+                round_trip(&key_package, |k, w| k.write(w), |b| KeyPackage::read(b)),
+            );
+            public_key_package = Some(round_trip(
+                &public,
+                |p, w| p.write(w),
+                |b| PublicKeyPackage::read(b),
+            ));
+        }
+        let public_key_package = public_key_package.unwrap();
+
+        // Every participant agrees on the group key.
+        for key_package in key_packages.values() {
+            assert_eq!(
+                key_package.group_public(),
+                public_key_package.group_public()
+            );
+        }
+        check_signing(&key_packages, &public_key_package);
+
+        // Missing first-round packages are rejected.
+        let mut partial = others(ids[0]);
+        partial.remove(&ids[1]);
+        let (secret, _) = dkg::part1(ids[0], 3, 2, OsRng).unwrap();
+        assert_eq!(
+            dkg::part2(secret, &partial).unwrap_err(),
+            Error::IncorrectNumberOfPackages
+        );
+    }
+
+    #[test]
+    fn malformed_encodings() {
+        let (shares, public_key_package) = generate_with_dealer(3, 2, OsRng).unwrap();
+        let share = shares.into_iter().next().unwrap().1;
+        let key_package = KeyPackage::try_from(share.clone()).unwrap();
+        let (_, commitments) = commit(&key_package, OsRng);
+
+        // Identifiers must be non-zero.
+        let mut bytes = vec![];
+        share.write(&mut bytes).unwrap();
+        bytes[..2].copy_from_slice(&[0, 0]);
+        assert!(SecretShare::read(&bytes[..]).is_err());
+
+        // The verifying share must match the signing share.
+        let mut bytes = vec![];
+        key_package.write(&mut bytes).unwrap();
+        bytes[34..66].copy_from_slice(&public_key_package.group_public.to_bytes());
+        assert!(KeyPackage::read(&bytes[..]).is_err());
+
+        // The threshold cannot exceed the number of participants.
+        let mut bytes = vec![];
+        public_key_package.write(&mut bytes).unwrap();
+        let len = bytes.len();
+        bytes[len - 2..].copy_from_slice(&4u16.to_le_bytes());
+        assert!(PublicKeyPackage::read(&bytes[..]).is_err());
+
+        // Identifiers must be in ascending order.
+        let mut bytes = vec![];
+        public_key_package.write(&mut bytes).unwrap();
+        bytes[1..3].copy_from_slice(&2u16.to_le_bytes());
+        assert!(PublicKeyPackage::read(&bytes[..]).is_err());
+
+        // Commitments cannot be the identity.
+        let mut bytes = commitments.to_bytes();
+        bytes[32..].copy_from_slice(&[0; 32]);
+        assert!(SigningCommitments::from_bytes(&bytes).is_none());
+
+        // Scalars must be canonically encoded.
+        assert!(SignatureShare::from_bytes(&[0xff; 32]).is_none());
+        assert!(dkg::Round2Package::from_bytes(&[0xff; 32]).is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! Helpers for Zcash binary encodings.
+
+use std::convert::TryInto;
+use std::io::{self, Read, Write};
+
+/// The largest compact size accepted by `zcashd` when parsing lengths and counts.
+pub(crate) const MAX_COMPACT_SIZE: usize = 0x0200_0000;
+
+/// Reads a canonically-encoded compact size.
+///
+/// Defined in [Zcash Protocol Spec § 7.1: Transaction Encoding and Consensus][txnencoding].
+///
+/// [txnencoding]: https://zips.z.cash/protocol/nu5.pdf#txnencoding
+pub(crate) fn read_compact_size<R: Read>(mut reader: R) -> io::Result<u64> {
+    let mut flag = [0; 1];
+    reader.read_exact(&mut flag)?;
+
+    let mut read_le = |len: usize| -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes[..len])?;
+        Ok(u64::from_le_bytes(bytes))
+    };
+
+    let (n, min) = match flag[0] {
+        0xfd => (read_le(2)?, 0xfd),
+        0xfe => (read_le(4)?, 0x1_0000),
+        0xff => (read_le(8)?, 0x1_0000_0000),
+        n => (n as u64, 0),
+    };
+
+    if n >= min {
+        Ok(n)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "non-canonical compact size",
+        ))
+    }
+}
+
+/// Writes a compact size in its canonical encoding.
+///
+/// Defined in [Zcash Protocol Spec § 7.1: Transaction Encoding and Consensus][txnencoding].
+///
+/// [txnencoding]: https://zips.z.cash/protocol/nu5.pdf#txnencoding
+pub(crate) fn write_compact_size<W: Write>(mut writer: W, n: u64) -> io::Result<()> {
+    if n < 0xfd {
+        writer.write_all(&[n as u8])
+    } else if n <= 0xffff {
+        writer.write_all(&[0xfd])?;
+        writer.write_all(&(n as u16).to_le_bytes())
+    } else if n <= 0xffff_ffff {
+        writer.write_all(&[0xfe])?;
+        writer.write_all(&(n as u32).to_le_bytes())
+    } else {
+        writer.write_all(&[0xff])?;
+        writer.write_all(&n.to_le_bytes())
+    }
+}
+
+/// Reads a compact size that is used as a length or count, and checks that it is no
+/// larger than `max`.
+pub(crate) fn read_compact_size_bounded<R: Read>(reader: R, max: usize) -> io::Result<usize> {
+    read_compact_size(reader)?
+        .try_into()
+        .ok()
+        .filter(|&n| n <= max)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "compact size too large"))
+}
+
+/// Reads a fixed-size byte array.
+pub(crate) fn read_bytes<R: Read, const N: usize>(mut reader: R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Returns an [`io::ErrorKind::InvalidData`] error with the given message.
+pub(crate) fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+    error: E,
+) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_compact_size, read_compact_size_bounded, write_compact_size};
+
+    #[test]
+    fn compact_size() {
+        for &n in &[0, 0xfc, 0xfd, 0xffff, 0x1_0000, 0xffff_ffff, 0x1_0000_0000] {
+            let mut buf = vec![];
+            write_compact_size(&mut buf, n).unwrap();
+            let mut cursor = &buf[..];
+            assert_eq!(read_compact_size(&mut cursor).unwrap(), n);
+            assert!(cursor.is_empty());
+        }
+
+        // Non-canonical encodings are rejected.
+        assert!(read_compact_size(&[0xfd, 0xfc, 0x00][..]).is_err());
+        assert!(read_compact_size(&[0xfe, 0xff, 0xff, 0x00, 0x00][..]).is_err());
+        // Truncated encodings are rejected.
+        assert!(read_compact_size(&[0xfd, 0x00][..]).is_err());
+
+        assert_eq!(read_compact_size_bounded(&[0xfc][..], 0xfc).unwrap(), 0xfc);
+        assert!(read_compact_size_bounded(&[0xfc][..], 0xfb).is_err());
+    }
+}
@@ -11,6 +11,8 @@ pub(crate) enum PrfExpand {
     OrchardRivk,
     Psi,
     OrchardDkOvk,
+    OrchardZip32Child,
+    OrchardRivkInternal,
 }
 
 impl PrfExpand {
@@ -22,7 +24,9 @@ impl PrfExpand {
             Self::OrchardNk => 0x07,
             Self::OrchardRivk => 0x08,
             Self::Psi => 0x09,
+            Self::OrchardZip32Child => 0x81,
             Self::OrchardDkOvk => 0x82,
+            Self::OrchardRivkInternal => 0x83,
         }
     }
 
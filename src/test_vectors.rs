@@ -1,3 +1,6 @@
 pub(crate) mod commitment_tree;
+pub(crate) mod f4jumble;
 pub(crate) mod keys;
 pub(crate) mod note_encryption;
+pub(crate) mod unified;
+pub(crate) mod zip32;
@@ -0,0 +1,135 @@
+//! Test vectors for F4Jumble, as defined in ZIP 316.
+
+pub(crate) struct TestVector {
+    pub(crate) normal: &'static [u8],
+    pub(crate) jumbled: &'static [u8],
+}
+
+pub(crate) fn test_vectors() -> Vec<TestVector> {
+    // Generated from an implementation of F4Jumble that is independent of this crate,
+    // with messages drawn from the same seeded RNG as
+    // https://github.com/zcash-hackworks/zcash-test-vectors/blob/master/f4jumble.py
+    // The first vector is identical to the first one published there.
+    vec![
+        TestVector {
+            normal: &[
+                0x5d, 0x7a, 0x8f, 0x73, 0x9a, 0x2d, 0x9e, 0x94, 0x5b, 0x0c, 0xe1, 0x52, 0xa8, 0x04,
+                0x9e, 0x29, 0x4c, 0x4d, 0x6e, 0x66, 0xb1, 0x64, 0x93, 0x9d, 0xaf, 0xfa, 0x2e, 0xf6,
+                0xee, 0x69, 0x21, 0x48, 0x1c, 0xdd, 0x86, 0xb3, 0xcc, 0x43, 0x18, 0xd9, 0x61, 0x4f,
+                0xc8, 0x20, 0x90, 0x5d, 0x04, 0x2b,
+            ],
+            jumbled: &[
+                0x03, 0x04, 0xd0, 0x29, 0x14, 0x1b, 0x99, 0x5d, 0xa5, 0x38, 0x7c, 0x12, 0x59, 0x70,
+                0x67, 0x35, 0x04, 0xd6, 0xc7, 0x64, 0xd9, 0x1e, 0xa6, 0xc0, 0x82, 0x12, 0x37, 0x70,
+                0xc7, 0x13, 0x9c, 0xcd, 0x88, 0xee, 0x27, 0x36, 0x8c, 0xd0, 0xc0, 0x92, 0x1a, 0x04,
+                0x44, 0xc8, 0xe5, 0x85, 0x8d, 0x22,
+            ],
+        },
+        TestVector {
+            normal: &[
+                0xb1, 0xef, 0x9c, 0xa3, 0xf2, 0x49, 0x88, 0xc7, 0xb3, 0x53, 0x42, 0x01, 0xcf, 0xb1,
+                0xcd, 0x8d, 0xbf, 0x69, 0xb8, 0x25, 0x0c, 0x18, 0xef, 0x41, 0x29, 0x4c, 0xa9, 0x79,
+                0x93, 0xdb, 0x54, 0x6c, 0x1f, 0xe0, 0x1f, 0x7e, 0x9c, 0x8e, 0x36, 0xd6, 0xa5, 0xe2,
+                0x9d, 0x4e, 0x30, 0xa7, 0x35, 0x94, 0xbf, 0x50, 0x98, 0x42, 0x1c, 0x69, 0x37, 0x8a,
+                0xf1, 0xe4, 0x0f, 0x64, 0xe1, 0x25, 0x94, 0x6f, 0x62, 0xc2, 0xfa, 0x7b, 0x2f, 0xec,
+                0xbc, 0xb6, 0x4b, 0x69, 0x68, 0x91, 0x2a, 0x63, 0x81, 0xce, 0x3d, 0xc1, 0x66,
+            ],
+            jumbled: &[
+                0x11, 0x65, 0xc8, 0x1e, 0x81, 0x34, 0x96, 0x3e, 0x74, 0x00, 0x91, 0xb1, 0x24, 0x6b,
+                0x8a, 0x65, 0xf6, 0xbe, 0x09, 0x52, 0xff, 0x61, 0x6e, 0x95, 0xcd, 0xe4, 0x21, 0x7a,
+                0xf4, 0x8a, 0xc5, 0xe9, 0x59, 0x6e, 0x1c, 0xaf, 0xde, 0x92, 0x9a, 0x92, 0x77, 0xfe,
+                0x85, 0xda, 0xc5, 0xc0, 0xc4, 0x67, 0x8c, 0xdf, 0x17, 0xed, 0x03, 0x28, 0x29, 0x0d,
+                0xdf, 0x0b, 0xec, 0x02, 0xbe, 0xfe, 0x06, 0xc5, 0xc7, 0x90, 0x3c, 0x07, 0xa7, 0x61,
+                0x08, 0x60, 0x48, 0x3f, 0x11, 0x2f, 0x50, 0x98, 0x2b, 0x42, 0xa1, 0xaa, 0x4d,
+            ],
+        },
+        TestVector {
+            normal: &[
+                0xd5, 0x6a, 0x1d, 0x62, 0xf5, 0xa8, 0xd7, 0x55, 0x1d, 0xb5, 0xfd, 0x93, 0x13, 0xe8,
+                0xc7, 0x20, 0x3d, 0x99, 0x6a, 0xf7, 0xd4, 0x77, 0x08, 0x37, 0x56, 0xd5, 0x9a, 0xf8,
+                0x0d, 0x06, 0xa7, 0x45, 0xf4, 0x4a, 0xb0, 0x23, 0x75, 0x2c, 0xb5, 0xb4, 0x06, 0xed,
+                0x89, 0x85, 0xe1, 0x81, 0x30, 0xab, 0x33, 0x36, 0x26, 0x97, 0xb0, 0xe4, 0xe4, 0xc7,
+                0x63, 0xcc, 0xb8, 0xf6, 0x76, 0x49, 0x5c, 0x22, 0x2f, 0x7f, 0xba, 0x1e, 0x31, 0xde,
+                0xfa, 0x3d, 0x5a, 0x57, 0xef, 0xc2, 0xe1, 0xe9, 0xb0, 0x1a, 0x03, 0x55, 0x87, 0xd5,
+                0xfb, 0x1a, 0x38, 0xe0, 0x1d, 0x94, 0x90, 0x3d, 0x3c, 0x3e, 0x0a, 0xd3, 0x36, 0x0c,
+                0x1d, 0x37, 0x10, 0xac, 0xd2, 0x0b, 0x18, 0x3e, 0x31, 0xd4, 0x9f, 0x25, 0xc9, 0xa1,
+                0x38, 0xf4, 0x9b, 0x1a, 0x53, 0x7e, 0xdc, 0xf0, 0x4b, 0xe3, 0x4a, 0x98, 0x51, 0xa7,
+                0xaf, 0x9d,
+            ],
+            jumbled: &[
+                0x6c, 0x04, 0xc5, 0x95, 0xb9, 0xa8, 0x06, 0x3d, 0x0d, 0xe5, 0x59, 0x69, 0x03, 0xf3,
+                0xd9, 0x01, 0x07, 0x20, 0x05, 0x49, 0xa8, 0xe4, 0x2d, 0x84, 0x70, 0xf7, 0x25, 0x9c,
+                0x3a, 0x4f, 0xbc, 0x4d, 0xf8, 0x28, 0x22, 0xeb, 0xc2, 0x76, 0x16, 0x26, 0x82, 0x7e,
+                0x26, 0xa1, 0x37, 0x82, 0x58, 0x48, 0xc6, 0x43, 0x62, 0x30, 0x0b, 0x32, 0x86, 0xc9,
+                0x6e, 0x2a, 0xc9, 0x6f, 0x36, 0x20, 0x53, 0x46, 0xe8, 0x1b, 0x94, 0x2e, 0x4c, 0x66,
+                0x2f, 0x36, 0x2c, 0x06, 0x0c, 0x44, 0xe1, 0x13, 0x3e, 0x8a, 0xd1, 0x9b, 0x9e, 0xe5,
+                0x05, 0x9d, 0xf9, 0xca, 0x53, 0x02, 0x75, 0x34, 0x9b, 0x29, 0x12, 0x33, 0x64, 0x77,
+                0xfa, 0x0e, 0xf4, 0x96, 0x49, 0xc9, 0xde, 0xf2, 0xa9, 0xc6, 0x8f, 0x70, 0x45, 0x6a,
+                0xd5, 0x79, 0x77, 0xf0, 0x7e, 0xee, 0x90, 0xb4, 0x43, 0x16, 0x1f, 0x3f, 0x02, 0x23,
+                0x63, 0x61,
+            ],
+        },
+        TestVector {
+            normal: &[
+                0xb6, 0x99, 0x0e, 0xd8, 0x3d, 0xd6, 0x4a, 0xf3, 0x59, 0x7c, 0x04, 0x32, 0x3e, 0xa5,
+                0x1b, 0x00, 0x52, 0xad, 0x80, 0x84, 0xa8, 0xb9, 0xda, 0x94, 0x8d, 0x32, 0x0d, 0xad,
+                0xd6, 0x4f, 0x54, 0x31, 0xe6, 0x1d, 0xdf, 0x65, 0x8d, 0x24, 0xae, 0x67, 0xc2, 0x2c,
+                0x8d, 0x13, 0x09, 0x13, 0x1f, 0xc0, 0x0f, 0xe7, 0xf2, 0x35, 0x73, 0x42, 0x76, 0xd3,
+                0x8d, 0x47, 0xf1, 0xe1, 0x91, 0xe0, 0x0c, 0x7a, 0x1d, 0x48, 0xaf, 0x04, 0x68, 0x27,
+                0x59, 0x1e, 0x97, 0x33, 0xa9, 0x7f, 0xa6, 0xb6, 0x79, 0xf3, 0xdc, 0x60, 0x1d, 0x00,
+                0x82, 0x85, 0xed, 0xcb, 0xda, 0xe6, 0x9c, 0xe8, 0xfc, 0x1b, 0xe4, 0xaa, 0xc0, 0x0f,
+                0xf2, 0x71, 0x1e, 0xbd, 0x93, 0x1d, 0xe5, 0x18, 0x85, 0x68, 0x78, 0xf7, 0x34, 0x76,
+                0xf2, 0x1a, 0x48, 0x2e, 0xc9, 0x37, 0x83, 0x65, 0xc8, 0xf7, 0x39, 0x3c, 0x94, 0xe2,
+                0x88, 0x53, 0x15,
+            ],
+            jumbled: &[
+                0xf3, 0x33, 0xe4, 0xb2, 0x2c, 0xe5, 0xcf, 0x91, 0x46, 0x12, 0x1b, 0x0f, 0xdf, 0x58,
+                0xc9, 0x92, 0x24, 0x38, 0xdd, 0x8a, 0x1f, 0x6f, 0x3d, 0xbe, 0xbc, 0x5c, 0x7d, 0x23,
+                0x1f, 0x26, 0xaa, 0x10, 0x81, 0x2c, 0x28, 0x20, 0x04, 0xd0, 0xb2, 0xc3, 0xa3, 0xd1,
+                0x17, 0x72, 0x2f, 0xde, 0x9f, 0xbe, 0xca, 0xdd, 0x12, 0x94, 0x21, 0x94, 0xaf, 0xa8,
+                0x1b, 0x63, 0xd4, 0x84, 0xc4, 0x28, 0x5f, 0x5a, 0xd1, 0xa2, 0xa1, 0xd9, 0x11, 0xdb,
+                0x7f, 0x17, 0x51, 0xfd, 0x67, 0xf0, 0xc5, 0x3e, 0xb5, 0x0b, 0xcc, 0x81, 0xf0, 0x79,
+                0xe6, 0xf8, 0xd8, 0x7f, 0xb8, 0x9a, 0x14, 0x3d, 0xce, 0xeb, 0x73, 0x9d, 0xa9, 0xde,
+                0x6c, 0x4e, 0x81, 0x19, 0x01, 0x38, 0x39, 0xa5, 0x15, 0xb5, 0xd0, 0x3f, 0x83, 0x60,
+                0x61, 0xec, 0x9a, 0xdf, 0x0f, 0x03, 0x9f, 0x5a, 0x09, 0x4f, 0x4d, 0x2a, 0xdc, 0x50,
+                0xbb, 0xad, 0x21,
+            ],
+        },
+        TestVector {
+            normal: &[
+                0xeb, 0x46, 0x71, 0x09, 0x8b, 0x79, 0x53, 0x5e, 0x79, 0x0f, 0xe5, 0x3e, 0x29, 0xfe,
+                0xf2, 0xb3, 0x76, 0x66, 0x97, 0xac, 0x32, 0xb4, 0xf4, 0x73, 0xf4, 0x68, 0xa0, 0x08,
+                0xe7, 0x23, 0x89, 0xfc, 0x03, 0x88, 0x0d, 0x78, 0x0c, 0xb0, 0x7f, 0xcf, 0xaa, 0xbe,
+                0x3f, 0x1a, 0x84, 0xb2, 0x7d, 0xb5, 0x9a, 0x4a, 0x15, 0x3d, 0x88, 0x2d, 0x2b, 0x21,
+                0x03, 0x59, 0x65, 0x55, 0xed, 0x94, 0x94, 0xc6, 0xac, 0x89, 0x3c, 0x49, 0x72, 0x38,
+                0x33, 0xec, 0x89, 0x26, 0xc1, 0x03, 0x95, 0x86, 0xa7, 0xaf, 0xcf, 0x4a, 0x0d, 0x9c,
+                0x73, 0x1e, 0x98, 0x5d, 0x99, 0x58, 0x9c, 0x8b, 0xb8, 0x38, 0xe8, 0xaa, 0xf7, 0x45,
+                0x53, 0x3e, 0xd9, 0xe8, 0xae, 0x3a, 0x1c, 0xd0, 0x74, 0xa5, 0x1a, 0x20, 0xda, 0x8a,
+                0xba, 0x18, 0xd1, 0xdb, 0xeb, 0xbc, 0x86, 0x2d, 0xed, 0x42, 0x43, 0x5e, 0x92, 0x47,
+                0x69, 0x30, 0xd0, 0x69, 0x89, 0x6c, 0xff, 0x30, 0xeb, 0x41, 0x4f, 0x72, 0x7b, 0x89,
+                0xe0, 0x01, 0xaf, 0xa2, 0xfb, 0x8d, 0xc3, 0x43, 0x6d, 0x75, 0xa4, 0xa6, 0xf2, 0x65,
+                0x72, 0x50, 0x4b, 0x19, 0x22, 0x32, 0xec, 0xb9, 0xf0, 0xc0, 0x24, 0x11, 0xe5, 0x25,
+                0x96, 0xbc, 0x5e, 0x90, 0x45, 0x7e, 0x74, 0x59, 0x39, 0xff, 0xed, 0xbd, 0x12, 0x86,
+                0x3c, 0xe7, 0x1a, 0x02, 0xaf, 0x11, 0x7d, 0x41, 0x7a, 0xdb, 0x3d, 0x15, 0xcc, 0x54,
+                0xdc, 0xb1, 0xfc, 0xe4,
+            ],
+            jumbled: &[
+                0x62, 0xb8, 0xac, 0xdb, 0xf8, 0x80, 0x9a, 0x43, 0xd5, 0xa7, 0x67, 0x75, 0xdd, 0x3e,
+                0xe6, 0x9e, 0x29, 0x27, 0x75, 0x29, 0x12, 0x01, 0x9c, 0x95, 0xe9, 0x04, 0x60, 0xdd,
+                0x0f, 0xcb, 0xce, 0x61, 0x2f, 0x70, 0x01, 0xf8, 0xb4, 0x5a, 0x1a, 0xa1, 0xd6, 0xd5,
+                0x27, 0xf9, 0x6e, 0x9c, 0x96, 0xe2, 0xae, 0x6b, 0x24, 0xd3, 0x2d, 0xd4, 0x73, 0x84,
+                0x53, 0x03, 0xe9, 0x00, 0xc3, 0x31, 0x52, 0xac, 0xa1, 0x56, 0x3d, 0x19, 0x02, 0xc0,
+                0xbe, 0x8d, 0xd0, 0x73, 0x91, 0xb0, 0xd5, 0x3b, 0x7e, 0x66, 0x47, 0x06, 0x62, 0x71,
+                0x50, 0x4e, 0x47, 0x64, 0xa1, 0xdc, 0xac, 0x1c, 0xa4, 0x36, 0xe3, 0xd6, 0x0f, 0xa7,
+                0xce, 0xf2, 0x5e, 0x98, 0x38, 0x16, 0xe2, 0x10, 0x80, 0xd5, 0xad, 0x15, 0x2f, 0x2c,
+                0xd0, 0x49, 0x81, 0x46, 0xa9, 0xb3, 0x1a, 0x0c, 0xf2, 0xe8, 0xa8, 0x97, 0x4a, 0x86,
+                0x01, 0x38, 0x9d, 0x83, 0x10, 0xb3, 0x40, 0x4c, 0x08, 0xd1, 0x13, 0x80, 0x64, 0x0a,
+                0x1c, 0x87, 0xf7, 0x68, 0xa7, 0x17, 0xec, 0x58, 0xa2, 0x63, 0x85, 0x9f, 0x81, 0x9a,
+                0xbf, 0x4f, 0xd5, 0xd2, 0x23, 0x4f, 0x0c, 0x34, 0xc0, 0x95, 0xca, 0x84, 0x2c, 0x95,
+                0x4c, 0x4e, 0x9f, 0xaa, 0x76, 0x61, 0x8e, 0x46, 0xee, 0x69, 0x7b, 0xc5, 0xd7, 0x7d,
+                0x94, 0xf1, 0x5a, 0x64, 0x25, 0x32, 0x1c, 0xec, 0x48, 0x35, 0x84, 0x8c, 0x4b, 0x60,
+                0xcf, 0x95, 0x5c, 0x33,
+            ],
+        },
+    ]
+}
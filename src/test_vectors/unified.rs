@@ -0,0 +1,386 @@
+//! Test vectors for ZIP 316 unified addresses and viewing keys, encoded for Mainnet.
+
+pub(crate) struct AddressTestVector {
+    pub(crate) p2pkh_bytes: Option<[u8; 20]>,
+    pub(crate) p2sh_bytes: Option<[u8; 20]>,
+    pub(crate) sapling_raw_addr: Option<[u8; 43]>,
+    pub(crate) orchard_raw_addr: Option<[u8; 43]>,
+    pub(crate) unknown_typecode: Option<u32>,
+    pub(crate) unknown_bytes: Option<&'static [u8]>,
+    pub(crate) unified_addr: &'static str,
+}
+
+pub(crate) struct FvkTestVector {
+    pub(crate) t_key_bytes: Option<[u8; 65]>,
+    pub(crate) sapling_fvk_bytes: Option<[u8; 128]>,
+    pub(crate) orchard_fvk_bytes: Option<[u8; 96]>,
+    pub(crate) unknown_fvk_typecode: Option<u32>,
+    pub(crate) unknown_fvk_bytes: Option<&'static [u8]>,
+    pub(crate) unified_fvk: &'static str,
+}
+
+pub(crate) struct IvkTestVector {
+    pub(crate) t_key_bytes: Option<[u8; 65]>,
+    pub(crate) sapling_ivk_bytes: Option<[u8; 64]>,
+    pub(crate) orchard_ivk_bytes: Option<[u8; 64]>,
+    pub(crate) unknown_ivk_typecode: Option<u32>,
+    pub(crate) unknown_ivk_bytes: Option<&'static [u8]>,
+    pub(crate) unified_ivk: &'static str,
+}
+
+pub(crate) fn address_test_vectors() -> Vec<AddressTestVector> {
+    // Generated from an implementation of ZIP 316 that is independent of this crate.
+    // The Orchard items are taken from the key components in `super::keys`; the other
+    // items are opaque byte strings.
+    vec![
+        AddressTestVector {
+            p2pkh_bytes: None,
+            p2sh_bytes: None,
+            sapling_raw_addr: None,
+            orchard_raw_addr: Some([
+                0x8f, 0xf3, 0x38, 0x69, 0x71, 0xcb, 0x64, 0xb8, 0xe7, 0x78, 0x99, 0x08, 0xdd, 0x8e,
+                0xbd, 0x7d, 0xe9, 0x2a, 0x68, 0xe5, 0x86, 0xa3, 0x4d, 0xb8, 0xfe, 0xa9, 0x99, 0xef,
+                0xd2, 0x01, 0x6f, 0xae, 0x76, 0x75, 0x0a, 0xfa, 0xe7, 0xee, 0x94, 0x16, 0x46, 0xbc,
+                0xb9,
+            ]),
+            unknown_typecode: None,
+            unknown_bytes: None,
+            unified_addr: "u1qylzskzykhk5l5vk6zlyqqruvskzv74hk20lmrllzy3vdz6pvny5t9zwlrm86ukw77y5pu8uep2m33s7sc7gn6aq0jm9neg5tsektyn9",
+        },
+        AddressTestVector {
+            p2pkh_bytes: Some([
+                0x8e, 0x4a, 0x81, 0xa3, 0xd8, 0xc2, 0x47, 0x38, 0x11, 0x13, 0x05, 0x4b, 0xd6, 0x1c,
+                0x3a, 0x3b, 0x25, 0x73, 0x37, 0xb1,
+            ]),
+            p2sh_bytes: None,
+            sapling_raw_addr: Some([
+                0x44, 0x94, 0x93, 0x06, 0xf3, 0xa6, 0x69, 0x6a, 0x31, 0x47, 0x95, 0x85, 0x9d, 0x35,
+                0x9f, 0x62, 0x19, 0x23, 0xb8, 0x42, 0xb4, 0x89, 0x7a, 0x3d, 0xb6, 0xcb, 0x48, 0x31,
+                0xfb, 0x77, 0xdf, 0x80, 0x51, 0x77, 0x29, 0x88, 0x36, 0xd6, 0x91, 0xe4, 0x1d, 0x48,
+                0xb7,
+            ]),
+            orchard_raw_addr: Some([
+                0x78, 0x07, 0xca, 0x65, 0x08, 0x58, 0x81, 0x4d, 0x50, 0x22, 0xa8, 0x3d, 0x3d, 0xe4,
+                0xd5, 0x2c, 0x77, 0xfd, 0x0b, 0x63, 0x0a, 0x40, 0xdc, 0x38, 0x21, 0x24, 0x87, 0xb2,
+                0xff, 0x6e, 0xee, 0xf5, 0x6d, 0x8c, 0x6a, 0x61, 0x63, 0xe8, 0x54, 0xaf, 0xf0, 0x41,
+                0x89,
+            ]),
+            unknown_typecode: None,
+            unknown_bytes: None,
+            unified_addr: "u1vv6u5m2smdk8dxaurcmzke5acumg32rkc49tz8xsgze3fct3rdxuufxmsjlq0narf7yrnddmh6xtguf37e54pltu338t3c3tfwk7p42ph9vvf2r0uv6f2mhydhd20h2xjy3pn66g39xhz9f36yq4gf9l9elkuackw7j22ys6h6marpuxy08fyd6ku8gh5fn6kkk5mrgud7qhk28ss7w",
+        },
+        AddressTestVector {
+            p2pkh_bytes: None,
+            p2sh_bytes: Some([
+                0x74, 0x18, 0xd3, 0x02, 0x2f, 0xc3, 0x94, 0x86, 0x86, 0xcd, 0x77, 0x68, 0xa6, 0x37,
+                0x1b, 0xc7, 0xef, 0x37, 0x4a, 0x85,
+            ]),
+            sapling_raw_addr: None,
+            orchard_raw_addr: Some([
+                0x64, 0x24, 0xf7, 0x1a, 0x3a, 0xd1, 0x97, 0x42, 0x64, 0x98, 0xf4, 0xec, 0xcb, 0x6a,
+                0x57, 0x80, 0x20, 0x42, 0x37, 0x98, 0x72, 0x32, 0xbc, 0x09, 0x8f, 0x89, 0xac, 0xc4,
+                0x75, 0xc3, 0xf7, 0x4b, 0xd6, 0x9e, 0x2f, 0x35, 0xd4, 0x47, 0x36, 0xf4, 0x8f, 0x3c,
+                0x14,
+            ]),
+            unknown_typecode: Some(0x4000),
+            unknown_bytes: Some(&[
+                0xe4, 0x6c, 0xdc, 0x12, 0x6b, 0x66, 0x66, 0x9b, 0x6f, 0x87, 0x26, 0x8b, 0x87, 0xb2,
+                0xf3, 0x72, 0x6b, 0x01, 0xb5, 0x5e, 0x0e, 0x2f, 0x62, 0x11,
+            ]),
+            unified_addr: "u19808klk592fg9yu5gwarvmw2a5qczu73ne6puzr90j596yhs6v39yctpkhvyt9er9zmg7eaktae7yfhwslkq7mxm64rd63tsyyn7xf9w9h2rqrnvlr9enxjug6qu7fyxxxhxwvths8gleeceddyuf8hkkppda9x3uy4ucjt3sg54cxydes6d28ht",
+        },
+        AddressTestVector {
+            p2pkh_bytes: None,
+            p2sh_bytes: None,
+            sapling_raw_addr: Some([
+                0xd9, 0x93, 0x54, 0x69, 0xb0, 0xb8, 0x59, 0x4c, 0xf5, 0x57, 0x95, 0x0b, 0x82, 0x47,
+                0x52, 0x6b, 0x09, 0xd1, 0xee, 0x62, 0x93, 0x84, 0x84, 0xdd, 0xbb, 0xda, 0xfc, 0x18,
+                0xcd, 0x3e, 0x4a, 0xbf, 0x7b, 0x0e, 0x03, 0x66, 0xba, 0x61, 0xb9, 0x66, 0x9e, 0xe9,
+                0xb4,
+            ]),
+            orchard_raw_addr: None,
+            unknown_typecode: Some(0x1234),
+            unknown_bytes: Some(&[
+                0x54, 0x80, 0xa8, 0x5d, 0xe3, 0xc2, 0x34, 0xf7, 0x48, 0x4f, 0x56, 0xa8, 0x12, 0x9d,
+                0x8a, 0xec, 0xf0, 0x74, 0x91, 0x88, 0x9a, 0xb5, 0x2e, 0x75, 0xc5, 0x9b, 0x91, 0xd6,
+                0xca, 0x32, 0xec,
+            ]),
+            unified_addr: "u1ylggnf7q4lkf0tcf0pypn0dwgemhd7lkdtqg9tenm5hcm8jka4z7psvfhxspzj2wtgvvanq7c500rctr8u06kpat86h5y2jw4y8yc3xqkgjhu37gs47lzhyvu7w47uclt5vmzvgze7r0fsjusndjuqytvs9wwsk0",
+        },
+        AddressTestVector {
+            p2pkh_bytes: Some([
+                0xde, 0xd6, 0x35, 0xef, 0xde, 0xc8, 0xf1, 0x77, 0x7a, 0x89, 0x01, 0x5b, 0x44, 0x31,
+                0x34, 0x01, 0x36, 0xb6, 0xc8, 0x45,
+            ]),
+            p2sh_bytes: None,
+            sapling_raw_addr: Some([
+                0x55, 0xe2, 0xda, 0x6b, 0xbc, 0xfc, 0x7b, 0x2b, 0x29, 0xaa, 0xf4, 0xa9, 0x02, 0xd1,
+                0x1b, 0x8a, 0xa7, 0x7b, 0xd5, 0x2e, 0xc7, 0x63, 0x4c, 0xc7, 0xca, 0x72, 0x46, 0x4a,
+                0x30, 0xd4, 0xaa, 0xe7, 0x4d, 0x24, 0x21, 0x75, 0xae, 0x6c, 0x94, 0x6d, 0xf0, 0xc6,
+                0xf2,
+            ]),
+            orchard_raw_addr: Some([
+                0xaa, 0xe3, 0x6e, 0x09, 0x4d, 0xe0, 0x7b, 0xc1, 0x6f, 0x89, 0x8e, 0xb6, 0x53, 0x3d,
+                0xcb, 0xff, 0xf0, 0xf6, 0xc1, 0xce, 0xef, 0xa8, 0x47, 0x99, 0xbd, 0xa3, 0xde, 0x73,
+                0x34, 0x32, 0x6c, 0xcd, 0x65, 0xf7, 0xce, 0x92, 0xff, 0x3d, 0x9e, 0x6e, 0x1f, 0x14,
+                0x0b,
+            ]),
+            unknown_typecode: Some(0x7f),
+            unknown_bytes: Some(&[
+                0xc6, 0x0c, 0x05, 0x0f, 0x28, 0xd9, 0x5d, 0xbe, 0x2f, 0x23, 0x7c, 0x5d, 0x8f, 0xbf,
+                0x54, 0xf1, 0xa8, 0x8e, 0xdb, 0xd8, 0x33, 0xd7, 0xdc, 0xa8, 0x6f, 0x62, 0x22, 0x78,
+                0x2f, 0x04, 0xbd, 0x41, 0x4b, 0xad, 0x2c, 0x2f, 0xfb, 0xe0,
+            ]),
+            unified_addr: "u144lazky6myveht88p2gkqjwe8t4zvm6rug6ufm8698jrn4gxu3ats24yfgc8sua63pxxjv2nu49vhufzyvje26ydr60hkplv6xaulhptqm7kt5utjcsww7k8qpgpdl4qf2e0tu2cdka5u79q7pze8gv8cngcv65hmke286lra92g2ghdc2ra8gtkq20valy9cwygv3jvv778w2dqqyfvd5l0dra66l5ex6lk0795u7ymnvgupxxmzhhs2mf7g6rqzqwwhfd7lhqskl623sc",
+        },
+    ]
+}
+
+pub(crate) fn fvk_test_vectors() -> Vec<FvkTestVector> {
+    // Generated from an implementation of ZIP 316 that is independent of this crate.
+    // The Orchard items are taken from the key components in `super::keys`; the other
+    // items are opaque byte strings.
+    vec![
+        FvkTestVector {
+            t_key_bytes: None,
+            sapling_fvk_bytes: None,
+            orchard_fvk_bytes: Some([
+                0x74, 0x0b, 0xbe, 0x5d, 0x05, 0x80, 0xb2, 0xca, 0xd4, 0x30, 0x18, 0x0d, 0x02, 0xcc,
+                0x12, 0x8b, 0x9a, 0x14, 0x0d, 0x5e, 0x07, 0xc1, 0x51, 0x72, 0x1d, 0xc1, 0x6d, 0x25,
+                0xd4, 0xe2, 0x0f, 0x15, 0x9f, 0x2f, 0x82, 0x67, 0x38, 0x94, 0x5a, 0xd0, 0x1f, 0x47,
+                0xf7, 0x0d, 0xb0, 0xc3, 0x67, 0xc2, 0x46, 0xc2, 0x0c, 0x61, 0xff, 0x55, 0x83, 0x94,
+                0x8c, 0x39, 0xde, 0xa9, 0x68, 0xfe, 0xfd, 0x1b, 0x02, 0x1c, 0xcf, 0x89, 0x60, 0x4f,
+                0x5f, 0x7c, 0xc6, 0xe0, 0x34, 0xb3, 0x2d, 0x33, 0x89, 0x08, 0xb8, 0x19, 0xfb, 0xe3,
+                0x25, 0xfe, 0xe6, 0x45, 0x8b, 0x56, 0xb4, 0xca, 0x71, 0xa7, 0xe4, 0x3d,
+            ]),
+            unknown_fvk_typecode: None,
+            unknown_fvk_bytes: None,
+            unified_fvk: "uview1hp2f9pawrvznv7hmrxd4a6w8swanpmafq5md59ecwxg66v6uqj7khl7uq506rqhj58d5gaw9yt0l870ryfxy6wyl48r8ytwaxpwdycv6fhhptlz8dnrwgy4ggtel5waqpzaaf2h0a5h49dhevxjy99k5t2rvgvlyrhk4av856uwkzzhlhmclqpgrp0qea",
+        },
+        FvkTestVector {
+            t_key_bytes: Some([
+                0x5e, 0xda, 0x49, 0x6d, 0x88, 0xd4, 0xd9, 0xcd, 0x72, 0x8a, 0x67, 0x06, 0xa9, 0xab,
+                0xb7, 0x78, 0xf8, 0x81, 0xa5, 0xa7, 0x13, 0x0c, 0xa3, 0xa2, 0x3c, 0x31, 0x10, 0x21,
+                0x00, 0xb1, 0x1d, 0xec, 0xfe, 0x9c, 0x66, 0x8e, 0xd9, 0x01, 0x03, 0xfd, 0x54, 0xfe,
+                0xb9, 0xde, 0x9c, 0x9b, 0x17, 0x5a, 0x86, 0xec, 0xe7, 0xc0, 0x40, 0x08, 0x12, 0xd4,
+                0x44, 0xbb, 0xd9, 0xe3, 0x2c, 0x7b, 0x20, 0x35, 0x36,
+            ]),
+            sapling_fvk_bytes: Some([
+                0x72, 0x19, 0x9b, 0xf6, 0xd2, 0xe3, 0xe9, 0x17, 0x34, 0x13, 0xdb, 0xf3, 0x93, 0x5d,
+                0x32, 0x8f, 0x37, 0xbf, 0x3e, 0x6c, 0xa8, 0xab, 0xcf, 0xf2, 0x30, 0xcb, 0x8b, 0xcd,
+                0x0b, 0x4b, 0x39, 0x69, 0xe8, 0x23, 0x68, 0x74, 0x99, 0xae, 0xa1, 0x66, 0xed, 0x3f,
+                0x9b, 0xe7, 0xcb, 0xf0, 0x68, 0x2a, 0x71, 0x61, 0x96, 0x90, 0x86, 0xff, 0xa2, 0xac,
+                0x36, 0x7b, 0xe4, 0x19, 0x18, 0x62, 0xea, 0xa7, 0x13, 0x55, 0xb6, 0xf7, 0x0f, 0x60,
+                0xb3, 0xfd, 0x5a, 0x23, 0x7f, 0xb2, 0x8f, 0xf6, 0x82, 0x65, 0xfd, 0xf5, 0x94, 0x31,
+                0x54, 0x11, 0xa3, 0xf6, 0x56, 0x4e, 0x88, 0x82, 0xf3, 0x0b, 0xd6, 0x8d, 0xc0, 0x44,
+                0xd4, 0x14, 0xd1, 0x80, 0xcc, 0xec, 0x6d, 0x4c, 0x96, 0xed, 0x08, 0x50, 0xd0, 0x46,
+                0x73, 0x9c, 0x81, 0x18, 0xa9, 0xa0, 0xc6, 0xec, 0x4b, 0x39, 0xde, 0xe7, 0x7b, 0x19,
+                0xb4, 0x68,
+            ]),
+            orchard_fvk_bytes: Some([
+                0x6d, 0xe1, 0x34, 0x98, 0x30, 0xd6, 0x6d, 0x7b, 0x97, 0xfe, 0x23, 0x1f, 0xc7, 0xb0,
+                0x2a, 0xd6, 0x43, 0x23, 0x62, 0x9c, 0xfe, 0xd1, 0xe3, 0xaa, 0x24, 0xef, 0x05, 0x2f,
+                0x56, 0xe4, 0x00, 0x2a, 0xa8, 0xb7, 0x3d, 0x97, 0x9b, 0x6e, 0xaa, 0xda, 0x89, 0x24,
+                0xbc, 0xbd, 0xc6, 0x3a, 0x9e, 0xf4, 0xe8, 0x73, 0x46, 0xf2, 0x30, 0xab, 0xa6, 0xbb,
+                0xe1, 0xe2, 0xb4, 0x3c, 0x5b, 0xea, 0x6b, 0x22, 0xda, 0xcb, 0x2f, 0x2a, 0x9c, 0xed,
+                0x36, 0x31, 0x71, 0x82, 0x1a, 0xaf, 0x5d, 0x8c, 0xd9, 0x02, 0xbc, 0x5e, 0x3a, 0x5a,
+                0x41, 0xfb, 0x51, 0xae, 0x61, 0xa9, 0xf0, 0x2d, 0xc8, 0x9d, 0x1d, 0x12,
+            ]),
+            unknown_fvk_typecode: None,
+            unknown_fvk_bytes: None,
+            unified_fvk: "uview1ww7hqj689qhc46fxerkfdym8mv890txxnpkd6qn6tx3jg5t2p58k3ded6kkqxpgak945shrvff7y35dsuaurd3rkun4l46mkngajdzf7upyd433rkgeett3g5l6fyr4f97nzkwfsaydfw7um2797ye69ylchc5z72sq92vkx9fxp7z48humelemv0fslvj2tamynwu7jhvn4wz4kzpymjaw9mrf0d6wax4mhz2srramm0lhaqhzlghya870gmzrsrq4gq6ywd5a97fjzlqqm4lm8quzhejesxum87qrll2sztvhq9mhz65t5qns8mysrvf8sy596l6cv20a85nzk7u4pgp7n85t5phxf5eqjqnpnf9wlgrqykwhr23j08w6xvjf59r30y2zy2ffajk0jv9lxry2g8crdtxjk3ztfqmvyc0p55jjkdrust7fudgtsyf99c7xdxpwk9z648rt5r7sg59fgrs9mwhn5sux4qf3sdqjam5dfuv2r",
+        },
+        FvkTestVector {
+            t_key_bytes: None,
+            sapling_fvk_bytes: None,
+            orchard_fvk_bytes: Some([
+                0xef, 0xa5, 0xf1, 0xde, 0xbe, 0xea, 0xd0, 0x94, 0x0a, 0x61, 0x9c, 0xe0, 0x01, 0x7b,
+                0xed, 0xb4, 0x26, 0x65, 0x7b, 0x2d, 0x07, 0x40, 0x66, 0x64, 0xd8, 0x95, 0x31, 0x2e,
+                0xa1, 0xc3, 0xb3, 0x34, 0x04, 0x51, 0x4e, 0xa0, 0x48, 0xb9, 0x43, 0x63, 0xde, 0xa7,
+                0xcb, 0x3b, 0xe8, 0xd6, 0x25, 0x82, 0xac, 0x52, 0x92, 0x2e, 0x08, 0x65, 0xf6, 0x62,
+                0x74, 0x3b, 0x05, 0xea, 0xe8, 0x71, 0x5f, 0x17, 0x2a, 0x32, 0x8f, 0x99, 0x4f, 0x6e,
+                0x5a, 0xd2, 0x9c, 0xa8, 0x11, 0xed, 0x34, 0x49, 0x68, 0xea, 0x2c, 0xfc, 0x3f, 0xd2,
+                0x31, 0x03, 0x0e, 0x37, 0xbb, 0xd5, 0x6d, 0xb4, 0x26, 0x40, 0x23, 0x1c,
+            ]),
+            unknown_fvk_typecode: Some(0x4000),
+            unknown_fvk_bytes: Some(&[
+                0x50, 0x9b, 0x75, 0x66, 0xb7, 0xc4, 0xe2, 0xfb, 0xed, 0xd6, 0xad, 0x6b, 0x75, 0xb1,
+                0xf5, 0xd0, 0xb9, 0x83, 0xe2, 0x56, 0xfe, 0xf2, 0x57, 0x01,
+            ]),
+            unified_fvk: "uview1r2ym9cfsa38nuknx4f790uhd24jg24hgdlcllq6gptunnsrr75kvu3cqesy608v90gq5nms9vwp6tdyfmtg5xvas50d3jchy24nlpfy2xrlewkwpwm2v59jdpgmdcqh0zeaf0x5e99kmmxaleqtykkr3463ug8eyl65x8l74t80ykhcfxk7jvrgewgzllm94pltc3sq2w72l7lxc32jjz5mmrudk4wpjj75s5h0crj",
+        },
+        FvkTestVector {
+            t_key_bytes: None,
+            sapling_fvk_bytes: Some([
+                0x34, 0xe7, 0xc4, 0x86, 0x0a, 0x1b, 0x48, 0x5e, 0x38, 0xf8, 0x33, 0xf7, 0x03, 0x34,
+                0xb3, 0xcc, 0x72, 0xca, 0x73, 0x36, 0x14, 0xe1, 0x56, 0x4e, 0xb7, 0x7a, 0xb3, 0x36,
+                0x20, 0xcd, 0xbb, 0xf7, 0xd4, 0xd6, 0x5d, 0xf7, 0x25, 0xf0, 0x2e, 0x48, 0x5f, 0x70,
+                0xe1, 0x25, 0x62, 0x58, 0x74, 0x62, 0xee, 0xe3, 0x1c, 0xbc, 0xde, 0x97, 0x99, 0x7b,
+                0x13, 0xc7, 0xba, 0x27, 0xaa, 0x77, 0x37, 0x93, 0x88, 0xff, 0x2f, 0xff, 0xeb, 0x51,
+                0x8a, 0xed, 0x33, 0x7f, 0x4f, 0x19, 0x5b, 0xb4, 0x3b, 0x2e, 0xa6, 0xc9, 0x5c, 0x69,
+                0xf9, 0x50, 0x60, 0x38, 0x7d, 0xd9, 0x98, 0xf6, 0x22, 0xbd, 0x8e, 0xed, 0xec, 0x41,
+                0xb4, 0x62, 0xb1, 0x39, 0x84, 0x68, 0x45, 0xcc, 0x8a, 0x12, 0xb4, 0x9d, 0x32, 0x0e,
+                0xea, 0x2b, 0x34, 0xe2, 0xca, 0x4b, 0x87, 0x90, 0x3b, 0x06, 0x11, 0x64, 0xda, 0x3c,
+                0x6a, 0x45,
+            ]),
+            orchard_fvk_bytes: None,
+            unknown_fvk_typecode: Some(0x1234),
+            unknown_fvk_bytes: Some(&[
+                0x1d, 0x8e, 0xc3, 0x4e, 0x1f, 0xab, 0x87, 0xcc, 0x4e, 0x93, 0xd6, 0x44, 0xba, 0x19,
+                0x5c, 0x51, 0xf6, 0x22, 0xe3, 0x32, 0x9d, 0xd0, 0x18, 0xa7, 0x4b, 0x1f, 0x6a, 0xe0,
+                0xaa, 0x89, 0x58,
+            ]),
+            unified_fvk: "uview1k737vuk2gx9wfqy5pjc3pg7n6nqktjna9yuhrm8fu84jy6mg6dzuzhcek6shfcylk2jnpk5lwvgkq4z8rtf7hdvuz9y3rmsauezt5wuc2q26jszqpew5wgf9xhpn0cmy686php5l2azz4apwz678nglk092nkmjsc5cutav80scz856hwxpd0s35gwpspya333tlmhe8v9t4s9nc9zkphr9snau8fhu6ezhde6x6m8qwcam822qp9tf8ug0uxh7a3vt0aeh2xwgmghecq2pwysx654esyu8mesq5y5k0",
+        },
+        FvkTestVector {
+            t_key_bytes: Some([
+                0xf3, 0xfe, 0x53, 0xec, 0x2b, 0x31, 0x2d, 0x66, 0x74, 0xa2, 0x59, 0xaa, 0xc9, 0x90,
+                0xfd, 0xe0, 0x13, 0x1c, 0xd7, 0xce, 0xe8, 0x39, 0x4f, 0x52, 0x13, 0x6c, 0xb6, 0x0e,
+                0x40, 0x2f, 0x12, 0xdf, 0xb7, 0xf0, 0xdf, 0xad, 0xa9, 0x69, 0xc0, 0x0e, 0x05, 0xcb,
+                0x89, 0xde, 0x32, 0x36, 0x2c, 0x1b, 0xcc, 0x31, 0x30, 0x80, 0xed, 0xe8, 0x5a, 0x86,
+                0x7e, 0x7e, 0xaf, 0xe3, 0xad, 0x47, 0x3b, 0x16, 0x40,
+            ]),
+            sapling_fvk_bytes: Some([
+                0x95, 0xa7, 0x79, 0xb7, 0x47, 0x3a, 0xbb, 0x1e, 0xbe, 0x08, 0xdd, 0x58, 0x0c, 0x7b,
+                0x9f, 0x26, 0x90, 0x90, 0x90, 0x24, 0xeb, 0xd4, 0xb2, 0xcf, 0x41, 0x26, 0x11, 0x8e,
+                0xa2, 0x5a, 0x60, 0xa2, 0x4f, 0x50, 0x7d, 0x83, 0x31, 0x73, 0x5b, 0x1e, 0x04, 0xec,
+                0x15, 0x4d, 0xe9, 0x9a, 0xbf, 0x5b, 0xff, 0x74, 0x4b, 0xee, 0x39, 0x53, 0x89, 0x61,
+                0x50, 0x6d, 0xcd, 0x11, 0xd6, 0x77, 0x62, 0x57, 0x91, 0xaf, 0xc8, 0xef, 0xe5, 0x59,
+                0x40, 0xa7, 0x47, 0x7c, 0x81, 0x51, 0x95, 0xcc, 0x03, 0x4e, 0x00, 0x38, 0x86, 0xa8,
+                0xe7, 0x6f, 0x16, 0x74, 0x8d, 0x84, 0x94, 0x2f, 0xda, 0xe4, 0xb7, 0xad, 0x84, 0x31,
+                0xa8, 0xf7, 0xbe, 0x77, 0x61, 0xe2, 0x98, 0x11, 0x24, 0xe1, 0xaa, 0xaa, 0xd5, 0x12,
+                0x3f, 0x1c, 0x52, 0x5b, 0x84, 0x61, 0x91, 0xc3, 0x57, 0xfd, 0xa9, 0xed, 0x5e, 0x99,
+                0x6d, 0x44,
+            ]),
+            orchard_fvk_bytes: Some([
+                0x0d, 0x26, 0x2d, 0xe3, 0x60, 0x94, 0x33, 0xfe, 0x5b, 0x7c, 0x86, 0x2b, 0xc4, 0x8e,
+                0xf5, 0x6d, 0x83, 0x20, 0x09, 0xf7, 0x24, 0x2e, 0x1f, 0x7c, 0x77, 0x0a, 0x12, 0x24,
+                0x1d, 0xfa, 0x28, 0x07, 0x51, 0xba, 0xf3, 0x33, 0xcf, 0xf1, 0xf2, 0xd0, 0xc7, 0xe3,
+                0xcf, 0xf4, 0xd3, 0x01, 0x29, 0x9d, 0xc1, 0xef, 0xe9, 0x83, 0x00, 0x31, 0x4a, 0x54,
+                0x19, 0x38, 0x02, 0x9b, 0x45, 0xcc, 0x15, 0x21, 0x22, 0x8f, 0xeb, 0x79, 0x21, 0x98,
+                0x73, 0xc7, 0xa7, 0x60, 0x6e, 0x52, 0x97, 0x3c, 0x85, 0xf4, 0x60, 0x46, 0x5a, 0x60,
+                0x59, 0x08, 0x39, 0x19, 0xed, 0x73, 0xeb, 0x80, 0x5c, 0x11, 0x83, 0x01,
+            ]),
+            unknown_fvk_typecode: Some(0x7f),
+            unknown_fvk_bytes: Some(&[
+                0x72, 0xd2, 0xd8, 0xb9, 0x6d, 0x1d, 0x3f, 0x3d, 0xb2, 0xfa, 0x6f, 0x00, 0x4e, 0x59,
+                0x99, 0xc8, 0x8c, 0x48, 0x41, 0x0b, 0x44, 0xa7, 0xd8, 0x7d, 0x97, 0x9c, 0x75, 0xe8,
+                0xe7, 0xd8, 0x2c, 0x30, 0x0c, 0xb7, 0xba, 0xf5, 0x9a, 0xc0,
+            ]),
+            unified_fvk: "uview174rxc5reku9wpngmxe45wxz3mqq84e8938ke5rkpnfvsy30d2maf8rt9rfscfffm20pv5cttstt03kq2f0m9e5qwgaqlr8sac9ewxdrg0eyfh042g6pp4ta7ddzud8z65ltewzx0am8afjghgk4h0dmjklgjl49rvftjv927lftzx240dju36tgxp80ltv38hhsu5kfc2e596xzajrvqgggnx8vkxtcc2guj4z2pk6q39q393jkjrklkkjx3q09k940nrfyszt84qd0y4asfj874crtsxvrnmsh29364pt6pg7gz5mzd6y4wqxxh0drnaaw9drug67cy8xmzpw586r9tn50jcj9k6czuspseguqc7uuspkf0l362khllw9awyxsfv94l4x5kte7c7n5qqnqjxtmd7rsfy3c4wv9h7kl4dxm0f4gfzzxfftvq97dazx8xq7t926h500n3xhz5grxcx7acw6gplqvahgt47ztay6ly3swlya5tevtshetnhfsq5a7weyacruapmejl5wq8jdng82j9jlrqv0hm4dg0lefnzys3nmfm",
+        },
+    ]
+}
+
+pub(crate) fn ivk_test_vectors() -> Vec<IvkTestVector> {
+    // Generated from an implementation of ZIP 316 that is independent of this crate.
+    // The Orchard items are taken from the key components in `super::keys`; the other
+    // items are opaque byte strings.
+    vec![
+        IvkTestVector {
+            t_key_bytes: None,
+            sapling_ivk_bytes: None,
+            orchard_ivk_bytes: Some([
+                0x31, 0xd6, 0xa6, 0x85, 0xbe, 0x57, 0x0f, 0x9f, 0xaf, 0x3c, 0xa8, 0xb0, 0x52, 0xe8,
+                0x87, 0x84, 0x0b, 0x2c, 0x9f, 0x8d, 0x67, 0x22, 0x4c, 0xa8, 0x2a, 0xef, 0xb9, 0xe2,
+                0xee, 0x5b, 0xed, 0xaf, 0x85, 0xc8, 0xb5, 0xcd, 0x1a, 0xc3, 0xec, 0x3a, 0xd7, 0x09,
+                0x21, 0x32, 0xf9, 0x7f, 0x01, 0x78, 0xb0, 0x75, 0xc8, 0x1a, 0x13, 0x9f, 0xd4, 0x60,
+                0xbb, 0xe0, 0xdf, 0xcd, 0x75, 0x51, 0x47, 0x24,
+            ]),
+            unknown_ivk_typecode: None,
+            unknown_ivk_bytes: None,
+            unified_ivk: "uivk15faj7g4a2plkera7cgtky85z8kur7h2v93euac63f8q6qhtteclfrruj8shdx36dp6fnkcqt06juq2ay20y7sp0gqfedzv8gcu5zqv7ysxlf50srksgqprp8fu2hjd2ugnzs7u8p4h",
+        },
+        IvkTestVector {
+            t_key_bytes: Some([
+                0xce, 0x02, 0x8e, 0xfc, 0x6f, 0x53, 0xae, 0xf0, 0x44, 0x30, 0x26, 0xd0, 0xcf, 0x5a,
+                0x7c, 0x92, 0x42, 0x24, 0xf1, 0xd3, 0xe9, 0x10, 0x30, 0x22, 0x1b, 0xb5, 0x13, 0x62,
+                0xab, 0x37, 0x43, 0x77, 0xf0, 0x97, 0x08, 0x5a, 0x25, 0x13, 0xd1, 0xe2, 0x90, 0xd5,
+                0x30, 0x05, 0x09, 0x93, 0x57, 0x21, 0xfd, 0xe5, 0xe6, 0x11, 0x7d, 0x7a, 0x2a, 0x9d,
+                0x6a, 0xcc, 0xea, 0x3e, 0x11, 0xea, 0xb0, 0xf0, 0x9a,
+            ]),
+            sapling_ivk_bytes: Some([
+                0xea, 0x27, 0xf2, 0x25, 0xe8, 0x7f, 0xc0, 0xd3, 0xdf, 0x37, 0x26, 0xab, 0x1f, 0x10,
+                0x15, 0x3d, 0xd4, 0x68, 0xbb, 0x32, 0x34, 0x1d, 0x8a, 0x00, 0x78, 0xbd, 0x6a, 0xaa,
+                0xe0, 0x9e, 0x27, 0xea, 0x44, 0x90, 0x9c, 0x22, 0xd8, 0x63, 0x0c, 0x15, 0xcb, 0x9c,
+                0x59, 0xd9, 0xad, 0x1c, 0x06, 0xb8, 0xa2, 0x35, 0x54, 0x03, 0xb8, 0x8a, 0x9c, 0x69,
+                0x42, 0x42, 0x9b, 0x8e, 0x3b, 0x5f, 0xdf, 0xeb,
+            ]),
+            orchard_ivk_bytes: Some([
+                0x9d, 0x9b, 0xd4, 0x45, 0x25, 0xe7, 0xae, 0x06, 0xb0, 0x3a, 0xe6, 0xd4, 0xae, 0xcd,
+                0xe6, 0xae, 0x09, 0x27, 0xa7, 0xc6, 0x67, 0xd5, 0xd9, 0xf8, 0x17, 0x6b, 0x54, 0x46,
+                0x95, 0xdf, 0xec, 0x11, 0x56, 0x3a, 0x6d, 0xb6, 0x0c, 0x74, 0xc2, 0xdb, 0x08, 0x49,
+                0x2c, 0xba, 0xe3, 0xbb, 0x08, 0x3f, 0x1a, 0xea, 0xbf, 0xfb, 0xcf, 0x42, 0x55, 0x1d,
+                0x0a, 0xc6, 0x4f, 0x26, 0x90, 0x53, 0x67, 0x11,
+            ]),
+            unknown_ivk_typecode: None,
+            unknown_ivk_bytes: None,
+            unified_ivk: "uivk1knacnszefqr4x5jczlfekrv4rr4y4h6fdacchlvju2yjmzauz96lpurnzugnp37augdnk5ckeft7hjtj2gmry3ada8xdrpt7mr359q6t3lgp63a97gfaueatr35vawdn8ztmwpe5dh29f87yg7ljqz3dten2jruv2wgqysjvv5sxl59ltysc5mzyrwe9e5rmv6p4l7ymwr8n32ly0pcslcd7dgve8jjvqcyeu3f2ck437e5xmdnusmfhnz0n6x40ah9mdskq5sutkj2nu22uu38pa2gjnzxah6pah9wl92rwh99f8yl557llh5te4c9nz5tm2yz92lc9sqwhhnwcr4kf4amm4d",
+        },
+        IvkTestVector {
+            t_key_bytes: None,
+            sapling_ivk_bytes: None,
+            orchard_ivk_bytes: Some([
+                0x23, 0x6b, 0xc3, 0xf3, 0xd0, 0x2f, 0x96, 0x02, 0x80, 0xee, 0xde, 0xde, 0x10, 0x8d,
+                0x36, 0x85, 0x04, 0x9f, 0x23, 0x9a, 0xa6, 0x7c, 0x48, 0x55, 0x8f, 0x7c, 0x01, 0xd3,
+                0xfd, 0x46, 0x9e, 0xcd, 0x60, 0x9e, 0xcb, 0xc3, 0xd8, 0xce, 0xe3, 0xbe, 0x2b, 0x2a,
+                0x23, 0x62, 0x95, 0x1f, 0x58, 0xb7, 0x44, 0x82, 0xad, 0xfa, 0xee, 0xe1, 0xc4, 0x0f,
+                0x94, 0x03, 0x04, 0x40, 0xf5, 0x58, 0xaa, 0x30,
+            ]),
+            unknown_ivk_typecode: Some(0x4000),
+            unknown_ivk_bytes: Some(&[
+                0xfa, 0xbe, 0xce, 0x9a, 0x96, 0xaf, 0xa4, 0x2c, 0x83, 0xf3, 0x9c, 0x8d, 0xdd, 0xbe,
+                0xd2, 0x20, 0x32, 0xed, 0x73, 0x03, 0x17, 0xcd, 0x84, 0x51,
+            ]),
+            unified_ivk: "uivk19aarp7uml0qq086z7jlhxrmzzfnmdk5ku2wtayr3l7sqv6qy4axfucvj8r2kp9j5qslmlhcnptfu4nswx9hstvsvleu4eqpcd8ngamw7c9hj2nkmamlq6fxdplp54mrwdcex2d492k9wqwhgezeanh32x9lc4dnpqwyvvssc4cwphqp2pn8swu",
+        },
+        IvkTestVector {
+            t_key_bytes: None,
+            sapling_ivk_bytes: Some([
+                0x43, 0xa4, 0x6d, 0x9c, 0x90, 0x69, 0xb0, 0xd3, 0x35, 0xbf, 0xc3, 0x71, 0xf2, 0x38,
+                0x4f, 0xad, 0x5b, 0x9f, 0x2a, 0xae, 0x0f, 0xb2, 0xc6, 0x55, 0x5a, 0x3a, 0xcf, 0xf2,
+                0x79, 0x72, 0x52, 0xe1, 0x2e, 0x62, 0x8d, 0x45, 0xc4, 0xa2, 0xe6, 0xb1, 0x0a, 0x7e,
+                0x16, 0x76, 0xa8, 0xcc, 0xc6, 0x5d, 0x04, 0x13, 0xcd, 0xac, 0x76, 0x66, 0xa2, 0xb1,
+                0x78, 0x98, 0x53, 0x67, 0xa3, 0xed, 0x17, 0x1b,
+            ]),
+            orchard_ivk_bytes: None,
+            unknown_ivk_typecode: Some(0x1234),
+            unknown_ivk_bytes: Some(&[
+                0x15, 0xd2, 0x26, 0xba, 0x26, 0x04, 0xdd, 0x21, 0x9d, 0xd1, 0xc6, 0x04, 0x87, 0x54,
+                0xd7, 0x9e, 0xa4, 0x9f, 0x28, 0x67, 0xd4, 0x11, 0x6a, 0xda, 0x96, 0xca, 0x73, 0x55,
+                0x3a, 0x7e, 0x29,
+            ]),
+            unified_ivk: "uivk103thv68qq6guuy7qg7lrkvx27kf7c47a2u69as99l9mz9qr8le79av9mcrhr5mrtgkzpprhwlprvysq5rjey5n5pvy8gqqaxd05l8qnye6ysqre7gvlvft0fmh8tle3dlj3aavzvn20fh678950fxcwn6ckdlnv60jzusdgr8u6u49s6zja6xy6erkpstn4zuk",
+        },
+        IvkTestVector {
+            t_key_bytes: Some([
+                0x63, 0x27, 0x2f, 0x88, 0xdb, 0x78, 0xbd, 0x94, 0x91, 0x75, 0x5d, 0xf2, 0xa9, 0xce,
+                0x64, 0x19, 0xa4, 0xd8, 0xf5, 0xcc, 0xdf, 0x1b, 0xb3, 0x6b, 0x3a, 0x79, 0x1b, 0x04,
+                0x81, 0xcf, 0x23, 0xc7, 0x91, 0x9b, 0xb5, 0x8a, 0xed, 0xfe, 0x98, 0xa3, 0x48, 0x0b,
+                0xb6, 0xf9, 0xf1, 0xcc, 0x76, 0xd0, 0xb8, 0x44, 0x04, 0xc7, 0xd8, 0x64, 0x0a, 0x7a,
+                0x60, 0x44, 0xaa, 0xc5, 0x66, 0x72, 0x17, 0x17, 0xe1,
+            ]),
+            sapling_ivk_bytes: Some([
+                0xb6, 0x5f, 0x19, 0x95, 0xdc, 0x1a, 0x7a, 0xf1, 0x60, 0x52, 0x0f, 0xdd, 0x6a, 0xa6,
+                0x58, 0x15, 0x47, 0xbc, 0x8f, 0xf3, 0x45, 0x92, 0xc9, 0x82, 0xfd, 0xd4, 0x9f, 0x6a,
+                0xc0, 0x57, 0xa7, 0x7c, 0xc3, 0x41, 0xae, 0x1b, 0x63, 0xa6, 0xbd, 0xf9, 0x70, 0x47,
+                0xd6, 0x09, 0x80, 0x0c, 0xa0, 0x23, 0xa4, 0xa2, 0xc2, 0x2c, 0x25, 0x1e, 0xdd, 0x9b,
+                0xb0, 0x80, 0x8f, 0x9f, 0x67, 0x5a, 0x9d, 0x64,
+            ]),
+            orchard_ivk_bytes: Some([
+                0xee, 0x19, 0xf8, 0xdd, 0xd9, 0xda, 0x06, 0x34, 0x24, 0x51, 0x43, 0xc4, 0xb4, 0x3a,
+                0xfc, 0x7d, 0x78, 0xc5, 0x49, 0xc8, 0x20, 0x54, 0xa9, 0xd8, 0x40, 0x07, 0xb5, 0x62,
+                0x17, 0xdb, 0xfd, 0xd6, 0x76, 0xf4, 0x9c, 0xf8, 0xa3, 0x19, 0x21, 0x85, 0x61, 0x6a,
+                0x9a, 0x0d, 0xa0, 0xc7, 0x6e, 0xc2, 0xc2, 0x75, 0x61, 0x59, 0xbc, 0xe1, 0x86, 0xa1,
+                0x86, 0x2b, 0x6e, 0x6e, 0x59, 0x44, 0x2d, 0x11,
+            ]),
+            unknown_ivk_typecode: Some(0x7f),
+            unknown_ivk_bytes: Some(&[
+                0xc8, 0x57, 0xe2, 0xca, 0xd4, 0x11, 0xb5, 0x56, 0x49, 0xa8, 0x4d, 0x03, 0xdd, 0xd6,
+                0x4c, 0x0a, 0x43, 0x8c, 0x7c, 0x67, 0x20, 0x05, 0x87, 0x35, 0xe4, 0x67, 0xdd, 0x05,
+                0x0c, 0x30, 0x5f, 0x73, 0x0e, 0xbe, 0x45, 0xbb, 0xff, 0x27,
+            ]),
+            unified_ivk: "uivk1ux779rf84wfh8hsdmrm38dtmp6pdvvymqsps58ppc5l64k3wzwmd90ejyuk3cxcecnyse3vgcdkcerlqmch45j8de8d2q0z7rtqt2ucwz8ju7zfc4fjwvn8vafhnuzqkfrf6qkarkj6qjut2vey5809z4ak20s4448x4lam20d3getg8vqz8hu68tgey4xah0uqw683z6nksuyywjaa354z3xe7nh7pm3ejp77zusweljmc5xnmvpzftqj4lug6p0vt5sred39lewtkt83evktq0hs3gf7fhxekgxcs43zysuuzuggzneutlka5439408635vasmng4w4hxvzlxz3mufjplpm0y66wv7dqrmxlaay6tyefntrfhusk8r8ygg54tzn5wta7y6x8k4v5k43tx3xge98h",
+        },
+    ]
+}
@@ -0,0 +1,83 @@
+//! Test vectors for Orchard ZIP 32 key derivation.
+
+pub(crate) struct TestVector {
+    pub(crate) sk: [u8; 32],
+    pub(crate) c: [u8; 32],
+    pub(crate) fp: [u8; 32],
+}
+
+pub(crate) fn test_vectors() -> Vec<TestVector> {
+    // Derived from seed = [0x00, 0x01, ..., 0x1f] along the path m/1'/2'/3', following
+    // https://github.com/zcash-hackworks/zcash-test-vectors/blob/master/orchard_zip32.py
+    // `fp` is the ZIP 32 fingerprint of the full viewing key derived from `sk`.
+    vec![
+        TestVector {
+            sk: [
+                0x7e, 0xee, 0x3c, 0x10, 0x17, 0x87, 0x09, 0x90, 0xa3, 0xdd, 0x68, 0x91, 0xb8, 0x2f,
+                0x80, 0xbe, 0x89, 0x76, 0xc1, 0xe7, 0xdc, 0x20, 0xd6, 0x08, 0x17, 0xa5, 0xe8, 0x8e,
+                0x8b, 0x2c, 0xd4, 0xb8,
+            ],
+            c: [
+                0xab, 0x8b, 0x7a, 0x00, 0x50, 0x9e, 0xf2, 0x0e, 0x46, 0x9b, 0x52, 0x92, 0xb6, 0x1d,
+                0x47, 0x4b, 0x7c, 0xff, 0xcb, 0x16, 0x57, 0x92, 0x4c, 0xda, 0x72, 0x02, 0x50, 0xae,
+                0x40, 0x52, 0x66, 0x77,
+            ],
+            fp: [
+                0xff, 0x4c, 0xda, 0x50, 0x02, 0xc8, 0xd1, 0x82, 0x05, 0x88, 0x07, 0xb8, 0x4e, 0x61,
+                0x6b, 0x6d, 0x33, 0x9e, 0x1b, 0xbe, 0xec, 0xea, 0x01, 0x65, 0x05, 0x68, 0xd8, 0x91,
+                0xa4, 0x38, 0xe7, 0x06,
+            ],
+        },
+        TestVector {
+            sk: [
+                0x98, 0xd7, 0x03, 0xfc, 0xb4, 0x05, 0x04, 0xc9, 0x5b, 0x3b, 0x6e, 0xd1, 0x0e, 0xcd,
+                0x50, 0x08, 0x2c, 0xff, 0x97, 0xdf, 0xd1, 0xdd, 0x9a, 0xa0, 0x91, 0x3c, 0x78, 0xf9,
+                0x77, 0xc9, 0x62, 0xaf,
+            ],
+            c: [
+                0x6a, 0x04, 0x1d, 0xfb, 0x9c, 0xfe, 0xbe, 0xe9, 0x7c, 0xb1, 0x85, 0x4f, 0xdc, 0x48,
+                0x1c, 0xc0, 0x4f, 0x02, 0xc9, 0x57, 0x7a, 0xa6, 0xf1, 0x3b, 0x2c, 0x44, 0x5b, 0x80,
+                0xa9, 0x66, 0x9a, 0x22,
+            ],
+            fp: [
+                0x32, 0xbb, 0xdc, 0x92, 0x1d, 0x06, 0x6f, 0x23, 0x5d, 0xc9, 0x3e, 0x91, 0x3b, 0x8f,
+                0xe1, 0xfd, 0x5b, 0x9f, 0x7f, 0x6a, 0x13, 0xd5, 0x6f, 0x18, 0xec, 0x0d, 0x36, 0x20,
+                0xd1, 0xf7, 0xb9, 0xa6,
+            ],
+        },
+        TestVector {
+            sk: [
+                0x99, 0xaf, 0xd8, 0x89, 0x4b, 0xaa, 0xd5, 0x87, 0x84, 0xd0, 0xec, 0x08, 0xf5, 0x14,
+                0x8e, 0xe2, 0xc2, 0xa1, 0x7b, 0x2b, 0x29, 0x4b, 0x08, 0xef, 0x9e, 0x0a, 0x0c, 0xf1,
+                0x4b, 0xcc, 0x09, 0x20,
+            ],
+            c: [
+                0x6d, 0xa8, 0xb5, 0x7a, 0x36, 0xc7, 0x7a, 0xd6, 0x41, 0x2a, 0x9d, 0xc0, 0x11, 0x5f,
+                0x12, 0xac, 0xed, 0x0e, 0xe0, 0x1c, 0x40, 0x2a, 0x0c, 0xf0, 0xa5, 0x07, 0xcb, 0x17,
+                0xfc, 0x7b, 0xbd, 0x1d,
+            ],
+            fp: [
+                0x36, 0xa5, 0x7c, 0x4f, 0xc5, 0xb8, 0xb4, 0xa3, 0xd6, 0x2f, 0x22, 0xa5, 0x50, 0x08,
+                0x78, 0xf3, 0x93, 0x85, 0x6b, 0x7e, 0xcc, 0xe7, 0x71, 0xad, 0x59, 0x7c, 0xa9, 0x64,
+                0xb9, 0x86, 0x37, 0xd9,
+            ],
+        },
+        TestVector {
+            sk: [
+                0x96, 0x43, 0x9e, 0xa3, 0x48, 0xa4, 0xb2, 0xce, 0x4e, 0xc7, 0xbe, 0xb4, 0x54, 0x3c,
+                0x70, 0x27, 0x4c, 0x8f, 0x76, 0x49, 0x5d, 0x60, 0xc5, 0xfa, 0x5f, 0x01, 0x8b, 0x68,
+                0xf3, 0xc3, 0x23, 0x67,
+            ],
+            c: [
+                0xb1, 0x96, 0xe9, 0xb5, 0x80, 0x9d, 0x76, 0x57, 0x7a, 0x89, 0x44, 0xc3, 0xf8, 0xc8,
+                0xa8, 0x3f, 0x93, 0xf0, 0xc8, 0xf5, 0xac, 0xe6, 0xe7, 0xbc, 0x9c, 0xe4, 0x39, 0x6c,
+                0x03, 0x4d, 0x93, 0xfe,
+            ],
+            fp: [
+                0xbe, 0x1a, 0x1b, 0x66, 0x1d, 0x2c, 0xa3, 0x19, 0x82, 0x2a, 0x32, 0x55, 0x0d, 0x6d,
+                0xc4, 0x88, 0xb6, 0x57, 0x1e, 0x0c, 0xd7, 0x81, 0xd5, 0x07, 0x8b, 0x8f, 0x7b, 0xa3,
+                0x66, 0xdd, 0xd3, 0x68,
+            ],
+        },
+    ]
+}
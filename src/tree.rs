@@ -7,7 +7,7 @@ use crate::{
     note::commitment::ExtractedNoteCommitment,
     primitives::sinsemilla::{i2lebsp_k, HashDomain},
 };
-use incrementalmerkletree::{Altitude, Hashable};
+use incrementalmerkletree::{bridgetree::BridgeTree, Altitude, Frontier, Hashable, Tree};
 use pasta_curves::{arithmetic::FieldExt, pallas};
 
 use ff::{Field, PrimeField, PrimeFieldBits};
@@ -16,6 +16,7 @@ use rand::RngCore;
 use serde::de::{Deserializer, Error};
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
 use std::iter;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -80,6 +81,53 @@ pub struct MerklePath {
 }
 
 impl MerklePath {
+    /// Constructs a Merkle path from the position of a leaf and its authentication path.
+    ///
+    /// The authentication path is ordered from the sibling of the leaf up to the child
+    /// of the root.
+    pub fn from_parts(
+        position: u32,
+        auth_path: [MerkleCrhOrchardOutput; MERKLE_DEPTH_ORCHARD],
+    ) -> Self {
+        MerklePath {
+            position,
+            auth_path: gen_const_array(|i| auth_path[i].0),
+        }
+    }
+
+    /// Reads a Merkle path from its serialized form.
+    ///
+    /// The encoding is the position of the leaf as a little-endian `u32`, followed by
+    /// the 32 nodes of the authentication path, each as a canonically-encoded Pallas
+    /// base field element. Returns an error if any node is not canonically encoded.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut position = [0; 4];
+        reader.read_exact(&mut position)?;
+
+        let mut auth_path = [pallas::Base::zero(); MERKLE_DEPTH_ORCHARD];
+        for node in auth_path.iter_mut() {
+            let mut bytes = [0; 32];
+            reader.read_exact(&mut bytes)?;
+            *node = Option::from(pallas::Base::from_bytes(&bytes)).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "non-canonical Merkle path node")
+            })?;
+        }
+
+        Ok(MerklePath {
+            position: u32::from_le_bytes(position),
+            auth_path,
+        })
+    }
+
+    /// Writes this Merkle path in the serialized form accepted by [`MerklePath::read`].
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.position.to_le_bytes())?;
+        for node in self.auth_path.iter() {
+            writer.write_all(&node.to_bytes())?;
+        }
+        Ok(())
+    }
+
     /// Generates a dummy Merkle path for use in dummy spent notes.
     pub(crate) fn dummy(mut rng: &mut impl RngCore) -> Self {
         MerklePath {
@@ -121,6 +169,90 @@ impl MerklePath {
     }
 }
 
+/// An Orchard note commitment tree that tracks the Merkle paths of marked notes.
+///
+/// This wraps an incremental [`BridgeTree`] of depth 32, retaining only the data needed
+/// to compute the current anchor and the Merkle paths for notes that have been marked
+/// (typically, the notes that the wallet can spend). It supports checkpointing the tree
+/// state (for example at each block boundary) and rewinding to a previous checkpoint
+/// when handling a chain reorganization.
+#[derive(Clone, Debug)]
+pub struct NoteCommitmentTree {
+    inner: BridgeTree<MerkleCrhOrchardOutput, { MERKLE_DEPTH_ORCHARD as u8 }>,
+}
+
+impl NoteCommitmentTree {
+    /// Constructs an empty note commitment tree that retains at most `max_checkpoints`
+    /// checkpoints.
+    pub fn new(max_checkpoints: usize) -> Self {
+        NoteCommitmentTree {
+            inner: BridgeTree::new(max_checkpoints),
+        }
+    }
+
+    /// Appends a note commitment to the tree.
+    ///
+    /// Returns `false` if the tree is full.
+    pub fn append(&mut self, cmx: &ExtractedNoteCommitment) -> bool {
+        self.inner.append(&MerkleCrhOrchardOutput::from_cmx(cmx))
+    }
+
+    /// Marks the most recently appended note commitment, so that its Merkle path is
+    /// retained as the tree grows.
+    ///
+    /// Returns the position of the marked note commitment, or `None` if the tree is
+    /// empty.
+    pub fn mark(&mut self) -> Option<u32> {
+        if self.inner.witness() {
+            self.inner
+                .current_position()
+                .map(|position| <usize>::from(position) as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Stops retaining the Merkle path for the given note commitment.
+    ///
+    /// Returns `false` if the note commitment was not marked.
+    pub fn unmark(&mut self, cmx: &ExtractedNoteCommitment) -> bool {
+        self.inner
+            .remove_witness(&MerkleCrhOrchardOutput::from_cmx(cmx))
+    }
+
+    /// Records the current state of the tree, so that it can later be restored with
+    /// [`NoteCommitmentTree::rewind`].
+    pub fn checkpoint(&mut self) {
+        self.inner.checkpoint()
+    }
+
+    /// Restores the tree to the state it had at the most recent checkpoint, and removes
+    /// that checkpoint.
+    ///
+    /// Returns `false` if there are no checkpoints to rewind to.
+    pub fn rewind(&mut self) -> bool {
+        self.inner.rewind()
+    }
+
+    /// Returns the current root of the tree.
+    pub fn root(&self) -> Anchor {
+        Anchor(self.inner.root().0)
+    }
+
+    /// Returns the Merkle path from the given marked note commitment to the current
+    /// root of the tree.
+    ///
+    /// Returns `None` if the note commitment was not marked.
+    pub fn witness(&self, cmx: &ExtractedNoteCommitment) -> Option<MerklePath> {
+        self.inner
+            .authentication_path(&MerkleCrhOrchardOutput::from_cmx(cmx))
+            .map(|(position, auth_path)| MerklePath {
+                position: <usize>::from(position) as u32,
+                auth_path: gen_const_array(|i| auth_path[i].0),
+            })
+    }
+}
+
 struct Pair {
     left: pallas::Base,
     right: pallas::Base,
@@ -282,6 +414,8 @@ pub mod testing {
 
     use std::convert::TryInto;
 
+    #[cfg(test)]
+    use crate::constants::util::gen_const_array;
     use crate::{
         constants::MERKLE_DEPTH_ORCHARD,
         note::{commitment::ExtractedNoteCommitment, testing::arb_note, Note},
@@ -294,9 +428,11 @@ pub mod testing {
     use proptest::collection::vec;
     use proptest::prelude::*;
 
-    #[cfg(test)]
-    use super::MerkleCrhOrchardOutput;
     use super::{hash_with_l, Anchor, MerklePath, Pair, EMPTY_ROOTS};
+    #[cfg(test)]
+    use super::{MerkleCrhOrchardOutput, NoteCommitmentTree};
+    #[cfg(test)]
+    use rand::rngs::OsRng;
 
     #[test]
     fn test_vectors() {
@@ -428,6 +564,101 @@ pub mod testing {
         }
     }
 
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(10))]
+        #[allow(clippy::redundant_closure)]
+        #[test]
+        fn merkle_path_serialization(
+            (notes_and_auth_paths, anchor) in (1usize..4).prop_flat_map(|n_notes| arb_tree(n_notes))
+        ) {
+            for (note, auth_path) in notes_and_auth_paths.iter() {
+                let mut buf = vec![];
+                auth_path.write(&mut buf).unwrap();
+                assert_eq!(buf.len(), 4 + 32 * MERKLE_DEPTH_ORCHARD);
+
+                let parsed = MerklePath::read(&buf[..]).unwrap();
+                assert_eq!(parsed.position(), auth_path.position());
+                assert_eq!(parsed.auth_path(), auth_path.auth_path());
+                assert_eq!(parsed.root(note.commitment().into()).unwrap(), anchor);
+
+                let from_parts = MerklePath::from_parts(
+                    auth_path.position(),
+                    gen_const_array(|i| MerkleCrhOrchardOutput(auth_path.auth_path()[i])),
+                );
+                assert_eq!(from_parts.auth_path(), auth_path.auth_path());
+
+                // Truncated encodings are rejected.
+                assert!(MerklePath::read(&buf[..buf.len() - 1]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn merkle_path_rejects_non_canonical_nodes() {
+        let mut buf = vec![0; 4 + 32 * MERKLE_DEPTH_ORCHARD];
+        assert!(MerklePath::read(&buf[..]).is_ok());
+
+        // 2^256 - 1 is at least p, so an all-ones node is not a canonical field element.
+        buf[4..36].copy_from_slice(&[0xff; 32]);
+        assert!(MerklePath::read(&buf[..]).is_err());
+    }
+
+    #[test]
+    fn note_commitment_tree() {
+        let mut rng = OsRng;
+        let mut tree = NoteCommitmentTree::new(10);
+        let empty_root = tree.root();
+
+        // Append some notes, marking every other one.
+        let notes: Vec<_> = (0..6)
+            .map(|_| Note::dummy(&mut rng, None).2.commitment().into())
+            .collect::<Vec<ExtractedNoteCommitment>>();
+        for (i, cmx) in notes.iter().enumerate() {
+            assert!(tree.append(cmx));
+            if i % 2 == 0 {
+                assert_eq!(tree.mark(), Some(i as u32));
+            }
+        }
+        let root = tree.root();
+        assert_ne!(root, empty_root);
+
+        for (i, cmx) in notes.iter().enumerate() {
+            match tree.witness(cmx) {
+                Some(path) => {
+                    assert_eq!(i % 2, 0);
+                    assert_eq!(path.position(), i as u32);
+                    assert_eq!(path.root(*cmx).unwrap(), root);
+                }
+                None => assert_eq!(i % 2, 1),
+            }
+        }
+
+        // Unmarked notes no longer have witnesses.
+        assert!(tree.unmark(&notes[0]));
+        assert!(tree.witness(&notes[0]).is_none());
+        assert!(!tree.unmark(&notes[1]));
+
+        // Paths for marked notes are updated as the tree grows, and rewinding restores
+        // the previous state.
+        tree.checkpoint();
+        let cmx: ExtractedNoteCommitment = Note::dummy(&mut rng, None).2.commitment().into();
+        assert!(tree.append(&cmx));
+        let new_root = tree.root();
+        assert_ne!(new_root, root);
+        assert_eq!(
+            tree.witness(&notes[2]).unwrap().root(notes[2]).unwrap(),
+            new_root
+        );
+
+        assert!(tree.rewind());
+        assert_eq!(tree.root(), root);
+        assert_eq!(
+            tree.witness(&notes[2]).unwrap().root(notes[2]).unwrap(),
+            root
+        );
+        assert!(!tree.rewind());
+    }
+
     #[test]
     fn empty_roots_incremental() {
         let tv_empty_roots = crate::test_vectors::commitment_tree::test_vectors().empty_roots;
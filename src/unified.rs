@@ -0,0 +1,865 @@
+//! Unified addresses and unified viewing keys containing Orchard items.
+//!
+//! Defined in [ZIP 316: Unified Addresses and Unified Viewing Keys][zip316].
+//!
+//! This crate only understands the Orchard items within a unified container. Items for
+//! other pools are carried as opaque byte strings, so that containers produced by other
+//! software can be round-tripped without loss.
+//!
+//! [zip316]: https://zips.z.cash/zip-0316
+
+use std::cmp::Ordering;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+use bech32::{self, FromBase32, ToBase32, Variant};
+
+use crate::{
+    keys::{FullViewingKey, IncomingViewingKey},
+    serialization::{read_compact_size, write_compact_size},
+    Address,
+};
+
+mod f4jumble;
+
+/// The length of the padding appended to the raw encoding of a unified container.
+const PADDING_LEN: usize = 16;
+
+/// The Zcash network for which a unified container is encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    /// Zcash Mainnet.
+    Main,
+    /// Zcash Testnet.
+    Test,
+    /// Private integration / regression testing networks.
+    Regtest,
+}
+
+/// The type of an item within a unified container.
+///
+/// Defined in [ZIP 316: Encoding of Unified Addresses][encoding].
+///
+/// [encoding]: https://zips.z.cash/zip-0316#encoding-of-unified-addresses
+#[derive(Clone, Copy, Debug)]
+pub enum Typecode {
+    /// A transparent P2PKH item.
+    P2pkh,
+    /// A transparent P2SH item.
+    P2sh,
+    /// A Sapling item.
+    Sapling,
+    /// An Orchard item.
+    Orchard,
+    /// An item with a typecode not recognised by this crate.
+    Unknown(u32),
+}
+
+impl From<u32> for Typecode {
+    fn from(typecode: u32) -> Self {
+        match typecode {
+            0x00 => Typecode::P2pkh,
+            0x01 => Typecode::P2sh,
+            0x02 => Typecode::Sapling,
+            0x03 => Typecode::Orchard,
+            _ => Typecode::Unknown(typecode),
+        }
+    }
+}
+
+impl From<Typecode> for u32 {
+    fn from(typecode: Typecode) -> Self {
+        match typecode {
+            Typecode::P2pkh => 0x00,
+            Typecode::P2sh => 0x01,
+            Typecode::Sapling => 0x02,
+            Typecode::Orchard => 0x03,
+            Typecode::Unknown(typecode) => typecode,
+        }
+    }
+}
+
+impl PartialEq for Typecode {
+    fn eq(&self, other: &Self) -> bool {
+        u32::from(*self) == u32::from(*other)
+    }
+}
+
+impl Eq for Typecode {}
+
+impl PartialOrd for Typecode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Typecode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        u32::from(*self).cmp(&u32::from(*other))
+    }
+}
+
+impl Typecode {
+    fn is_transparent(&self) -> bool {
+        matches!(self, Typecode::P2pkh | Typecode::P2sh)
+    }
+}
+
+/// Errors that may be produced when constructing or parsing a unified container.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The string or its raw encoding is malformed.
+    InvalidEncoding(String),
+    /// The string does not have a human-readable part for the expected container type.
+    UnknownPrefix(String),
+    /// The items within the container are not in ascending typecode order.
+    InvalidTypecodeOrder,
+    /// The container contains more than one item with the given typecode.
+    DuplicateTypecode(Typecode),
+    /// The container contains both a P2PKH and a P2SH item.
+    BothP2phkAndP2sh,
+    /// The container contains only transparent items.
+    OnlyTransparent,
+    /// The item with the given typecode is not valid for this container.
+    InvalidItem(Typecode),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidEncoding(msg) => write!(f, "Invalid encoding: {}", msg),
+            ParseError::UnknownPrefix(hrp) => write!(f, "Unknown prefix: {}", hrp),
+            ParseError::InvalidTypecodeOrder => {
+                write!(f, "Items are not in ascending typecode order")
+            }
+            ParseError::DuplicateTypecode(typecode) => {
+                write!(f, "Duplicate item with typecode {}", u32::from(*typecode))
+            }
+            ParseError::BothP2phkAndP2sh => write!(f, "Both P2PKH and P2SH items present"),
+            ParseError::OnlyTransparent => write!(f, "Only transparent items present"),
+            ParseError::InvalidItem(typecode) => {
+                write!(f, "Invalid item with typecode {}", u32::from(*typecode))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An item within a unified container.
+trait Item: Sized {
+    fn typecode(&self) -> Typecode;
+
+    fn data(&self) -> Vec<u8>;
+
+    fn parse(typecode: Typecode, data: &[u8]) -> Result<Self, ParseError>;
+}
+
+/// Parses a fixed-length opaque item.
+fn parse_fixed<const N: usize>(typecode: Typecode, data: &[u8]) -> Result<[u8; N], ParseError> {
+    data.try_into()
+        .map_err(|_| ParseError::InvalidItem(typecode))
+}
+
+/// A receiver within a [`UnifiedAddress`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Receiver {
+    /// An Orchard payment address.
+    Orchard(Address),
+    /// The raw encoding of a Sapling payment address.
+    Sapling([u8; 43]),
+    /// A transparent P2PKH public key hash.
+    P2pkh([u8; 20]),
+    /// A transparent P2SH script hash.
+    P2sh([u8; 20]),
+    /// A receiver with a typecode not recognised by this crate.
+    Unknown {
+        /// The typecode of this receiver.
+        typecode: u32,
+        /// The encoding of this receiver.
+        data: Vec<u8>,
+    },
+}
+
+impl Item for Receiver {
+    fn typecode(&self) -> Typecode {
+        match self {
+            Receiver::Orchard(_) => Typecode::Orchard,
+            Receiver::Sapling(_) => Typecode::Sapling,
+            Receiver::P2pkh(_) => Typecode::P2pkh,
+            Receiver::P2sh(_) => Typecode::P2sh,
+            Receiver::Unknown { typecode, .. } => Typecode::Unknown(*typecode),
+        }
+    }
+
+    fn data(&self) -> Vec<u8> {
+        match self {
+            Receiver::Orchard(addr) => addr.to_raw_address_bytes().to_vec(),
+            Receiver::Sapling(data) => data.to_vec(),
+            Receiver::P2pkh(data) => data.to_vec(),
+            Receiver::P2sh(data) => data.to_vec(),
+            Receiver::Unknown { data, .. } => data.clone(),
+        }
+    }
+
+    fn parse(typecode: Typecode, data: &[u8]) -> Result<Self, ParseError> {
+        match typecode {
+            Typecode::Orchard => Option::from(Address::from_raw_address_bytes(&parse_fixed(
+                typecode, data,
+            )?))
+            .map(Receiver::Orchard)
+            .ok_or(ParseError::InvalidItem(typecode)),
+            Typecode::Sapling => parse_fixed(typecode, data).map(Receiver::Sapling),
+            Typecode::P2pkh => parse_fixed(typecode, data).map(Receiver::P2pkh),
+            Typecode::P2sh => parse_fixed(typecode, data).map(Receiver::P2sh),
+            Typecode::Unknown(typecode) => Ok(Receiver::Unknown {
+                typecode,
+                data: data.to_vec(),
+            }),
+        }
+    }
+}
+
+/// A viewing key within a [`UnifiedFullViewingKey`].
+#[derive(Clone, Debug)]
+pub enum Fvk {
+    /// An Orchard full viewing key.
+    Orchard(FullViewingKey),
+    /// The encoding of a Sapling full viewing key.
+    Sapling([u8; 128]),
+    /// The encoding of a transparent P2PKH extended public key.
+    P2pkh([u8; 65]),
+    /// A viewing key with a typecode not recognised by this crate.
+    Unknown {
+        /// The typecode of this viewing key.
+        typecode: u32,
+        /// The encoding of this viewing key.
+        data: Vec<u8>,
+    },
+}
+
+impl Item for Fvk {
+    fn typecode(&self) -> Typecode {
+        match self {
+            Fvk::Orchard(_) => Typecode::Orchard,
+            Fvk::Sapling(_) => Typecode::Sapling,
+            Fvk::P2pkh(_) => Typecode::P2pkh,
+            Fvk::Unknown { typecode, .. } => Typecode::Unknown(*typecode),
+        }
+    }
+
+    fn data(&self) -> Vec<u8> {
+        match self {
+            Fvk::Orchard(fvk) => fvk.to_bytes().to_vec(),
+            Fvk::Sapling(data) => data.to_vec(),
+            Fvk::P2pkh(data) => data.to_vec(),
+            Fvk::Unknown { data, .. } => data.clone(),
+        }
+    }
+
+    fn parse(typecode: Typecode, data: &[u8]) -> Result<Self, ParseError> {
+        match typecode {
+            Typecode::Orchard => FullViewingKey::from_bytes(&parse_fixed(typecode, data)?)
+                .map(Fvk::Orchard)
+                .ok_or(ParseError::InvalidItem(typecode)),
+            Typecode::Sapling => parse_fixed(typecode, data).map(Fvk::Sapling),
+            Typecode::P2pkh => parse_fixed(typecode, data).map(Fvk::P2pkh),
+            Typecode::P2sh => Err(ParseError::InvalidItem(typecode)),
+            Typecode::Unknown(typecode) => Ok(Fvk::Unknown {
+                typecode,
+                data: data.to_vec(),
+            }),
+        }
+    }
+}
+
+/// A viewing key within a [`UnifiedIncomingViewingKey`].
+#[derive(Clone, Debug)]
+pub enum Ivk {
+    /// An Orchard incoming viewing key.
+    Orchard(IncomingViewingKey),
+    /// The encoding of a Sapling incoming viewing key.
+    Sapling([u8; 64]),
+    /// The encoding of a transparent P2PKH external extended public key.
+    P2pkh([u8; 65]),
+    /// A viewing key with a typecode not recognised by this crate.
+    Unknown {
+        /// The typecode of this viewing key.
+        typecode: u32,
+        /// The encoding of this viewing key.
+        data: Vec<u8>,
+    },
+}
+
+impl Item for Ivk {
+    fn typecode(&self) -> Typecode {
+        match self {
+            Ivk::Orchard(_) => Typecode::Orchard,
+            Ivk::Sapling(_) => Typecode::Sapling,
+            Ivk::P2pkh(_) => Typecode::P2pkh,
+            Ivk::Unknown { typecode, .. } => Typecode::Unknown(*typecode),
+        }
+    }
+
+    fn data(&self) -> Vec<u8> {
+        match self {
+            Ivk::Orchard(ivk) => ivk.to_bytes().to_vec(),
+            Ivk::Sapling(data) => data.to_vec(),
+            Ivk::P2pkh(data) => data.to_vec(),
+            Ivk::Unknown { data, .. } => data.clone(),
+        }
+    }
+
+    fn parse(typecode: Typecode, data: &[u8]) -> Result<Self, ParseError> {
+        match typecode {
+            Typecode::Orchard => Option::from(IncomingViewingKey::from_bytes(&parse_fixed(
+                typecode, data,
+            )?))
+            .map(Ivk::Orchard)
+            .ok_or(ParseError::InvalidItem(typecode)),
+            Typecode::Sapling => parse_fixed(typecode, data).map(Ivk::Sapling),
+            Typecode::P2pkh => parse_fixed(typecode, data).map(Ivk::P2pkh),
+            Typecode::P2sh => Err(ParseError::InvalidItem(typecode)),
+            Typecode::Unknown(typecode) => Ok(Ivk::Unknown {
+                typecode,
+                data: data.to_vec(),
+            }),
+        }
+    }
+}
+
+/// The human-readable parts for each network of a unified container type.
+struct Hrps {
+    main: &'static str,
+    test: &'static str,
+    regtest: &'static str,
+}
+
+impl Hrps {
+    fn for_network(&self, network: Network) -> &'static str {
+        match network {
+            Network::Main => self.main,
+            Network::Test => self.test,
+            Network::Regtest => self.regtest,
+        }
+    }
+
+    fn network(&self, hrp: &str) -> Option<Network> {
+        if hrp == self.main {
+            Some(Network::Main)
+        } else if hrp == self.test {
+            Some(Network::Test)
+        } else if hrp == self.regtest {
+            Some(Network::Regtest)
+        } else {
+            None
+        }
+    }
+}
+
+const ADDRESS_HRPS: Hrps = Hrps {
+    main: "u",
+    test: "utest",
+    regtest: "uregtest",
+};
+
+const FVK_HRPS: Hrps = Hrps {
+    main: "uview",
+    test: "uviewtest",
+    regtest: "uviewregtest",
+};
+
+const IVK_HRPS: Hrps = Hrps {
+    main: "uivk",
+    test: "uivktest",
+    regtest: "uivkregtest",
+};
+
+/// Returns the raw encoding of the given items, without checking their validity.
+fn raw_encoding<I: Item>(hrp: &str, items: &[I]) -> Vec<u8> {
+    let mut raw = vec![];
+    for item in items {
+        let data = item.data();
+        write_compact_size(&mut raw, u32::from(item.typecode()) as u64).unwrap();
+        write_compact_size(&mut raw, data.len() as u64).unwrap();
+        raw.extend_from_slice(&data);
+    }
+
+    let mut padding = [0; PADDING_LEN];
+    padding[..hrp.len()].copy_from_slice(hrp.as_bytes());
+    raw.extend_from_slice(&padding);
+
+    raw
+}
+
+/// Encodes the given items as a unified container, without checking their validity.
+///
+/// # Panics
+///
+/// Panics if the raw encoding of the items is not a valid length for F4Jumble.
+fn encode_items<I: Item>(hrp: &str, items: &[I]) -> String {
+    let jumbled = f4jumble::f4jumble(&raw_encoding(hrp, items)).expect("valid length");
+    bech32::encode(hrp, jumbled.to_base32(), Variant::Bech32m).expect("hrp is valid")
+}
+
+/// Checks that the given items form a valid unified container.
+fn check_items<I: Item>(hrp: &str, items: &[I]) -> Result<(), ParseError> {
+    let mut typecodes: Vec<_> = items.iter().map(|item| item.typecode()).collect();
+
+    if typecodes.windows(2).any(|w| w[0] > w[1]) {
+        return Err(ParseError::InvalidTypecodeOrder);
+    }
+    typecodes.sort();
+    if let Some(w) = typecodes.windows(2).find(|w| w[0] == w[1]) {
+        return Err(ParseError::DuplicateTypecode(w[0]));
+    }
+    if typecodes.contains(&Typecode::P2pkh) && typecodes.contains(&Typecode::P2sh) {
+        return Err(ParseError::BothP2phkAndP2sh);
+    }
+    if typecodes.iter().all(|typecode| typecode.is_transparent()) {
+        return Err(ParseError::OnlyTransparent);
+    }
+
+    if f4jumble::VALID_LENGTH.contains(&raw_encoding(hrp, items).len()) {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidEncoding(
+            "Items have an invalid total length".to_owned(),
+        ))
+    }
+}
+
+/// Sorts the given items into ascending typecode order, and then checks that they form
+/// a valid unified container.
+fn sort_and_check_items<I: Item>(hrp: &str, mut items: Vec<I>) -> Result<Vec<I>, ParseError> {
+    // Ensure that every item would be accepted by the decoder. This catches unknown
+    // items that reuse the typecode of a known item type.
+    for item in &items {
+        I::parse(u32::from(item.typecode()).into(), &item.data())?;
+    }
+
+    items.sort_by_key(|item| item.typecode());
+    check_items(hrp, &items).map(|()| items)
+}
+
+/// Decodes a unified container with one of the given human-readable parts.
+fn decode_items<I: Item>(hrps: &Hrps, s: &str) -> Result<(Network, Vec<I>), ParseError> {
+    let (hrp, data, variant) =
+        bech32::decode(s).map_err(|e| ParseError::InvalidEncoding(e.to_string()))?;
+    let network = hrps
+        .network(&hrp)
+        .ok_or_else(|| ParseError::UnknownPrefix(hrp.clone()))?;
+    if variant != Variant::Bech32m {
+        return Err(ParseError::InvalidEncoding(
+            "Unified containers must use Bech32m".to_owned(),
+        ));
+    }
+
+    let jumbled =
+        Vec::<u8>::from_base32(&data).map_err(|e| ParseError::InvalidEncoding(e.to_string()))?;
+    let raw = f4jumble::f4jumble_inv(&jumbled)
+        .ok_or_else(|| ParseError::InvalidEncoding("Invalid length".to_owned()))?;
+
+    // The raw encoding must end with the HRP, padded to 16 bytes with zeroes.
+    let (mut cursor, padding) = raw.split_at(raw.len() - PADDING_LEN);
+    if &padding[..hrp.len()] != hrp.as_bytes() || padding[hrp.len()..].iter().any(|&b| b != 0) {
+        return Err(ParseError::InvalidEncoding("Invalid padding".to_owned()));
+    }
+
+    let mut items = vec![];
+    while !cursor.is_empty() {
+        let typecode = read_compact_size(&mut cursor)
+            .ok()
+            .and_then(|typecode| u32::try_from(typecode).ok())
+            .ok_or_else(|| ParseError::InvalidEncoding("Invalid typecode".to_owned()))?;
+        let len = read_compact_size(&mut cursor)
+            .ok()
+            .and_then(|len| usize::try_from(len).ok())
+            .filter(|&len| len <= cursor.len())
+            .ok_or_else(|| ParseError::InvalidEncoding("Invalid item length".to_owned()))?;
+        let (data, rest) = cursor.split_at(len);
+        items.push(I::parse(typecode.into(), data)?);
+        cursor = rest;
+    }
+
+    check_items(&hrp, &items)?;
+    Ok((network, items))
+}
+
+/// A Unified Address.
+///
+/// Defined in [ZIP 316: Unified Addresses][unifiedaddresses].
+///
+/// [unifiedaddresses]: https://zips.z.cash/zip-0316#encoding-of-unified-addresses
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnifiedAddress(Vec<Receiver>);
+
+impl UnifiedAddress {
+    /// Constructs a unified address from the given receivers.
+    ///
+    /// The receivers are placed in ascending typecode order. Returns an error if the
+    /// receivers do not form a valid unified address.
+    pub fn try_from_items(receivers: Vec<Receiver>) -> Result<Self, ParseError> {
+        sort_and_check_items(ADDRESS_HRPS.main, receivers).map(UnifiedAddress)
+    }
+
+    /// Returns the receivers within this unified address, in ascending typecode order.
+    pub fn items(&self) -> &[Receiver] {
+        &self.0
+    }
+
+    /// Returns the Orchard receiver within this unified address, if any.
+    pub fn orchard(&self) -> Option<&Address> {
+        self.0.iter().find_map(|receiver| match receiver {
+            Receiver::Orchard(addr) => Some(addr),
+            _ => None,
+        })
+    }
+
+    /// Encodes this unified address for the given network.
+    pub fn encode(&self, network: Network) -> String {
+        encode_items(ADDRESS_HRPS.for_network(network), &self.0)
+    }
+
+    /// Decodes a unified address, returning it along with the network it is for.
+    pub fn decode(s: &str) -> Result<(Network, Self), ParseError> {
+        decode_items(&ADDRESS_HRPS, s).map(|(network, items)| (network, UnifiedAddress(items)))
+    }
+}
+
+/// A Unified Full Viewing Key.
+///
+/// Defined in [ZIP 316: Unified Viewing Keys][unifiedviewingkeys].
+///
+/// [unifiedviewingkeys]: https://zips.z.cash/zip-0316#encoding-of-unified-full-incoming-viewing-keys
+#[derive(Clone, Debug)]
+pub struct UnifiedFullViewingKey(Vec<Fvk>);
+
+impl UnifiedFullViewingKey {
+    /// Constructs a unified full viewing key from the given viewing keys.
+    ///
+    /// The viewing keys are placed in ascending typecode order. Returns an error if the
+    /// viewing keys do not form a valid unified full viewing key.
+    pub fn try_from_items(fvks: Vec<Fvk>) -> Result<Self, ParseError> {
+        sort_and_check_items(FVK_HRPS.main, fvks).map(UnifiedFullViewingKey)
+    }
+
+    /// Returns the viewing keys within this unified full viewing key, in ascending
+    /// typecode order.
+    pub fn items(&self) -> &[Fvk] {
+        &self.0
+    }
+
+    /// Returns the Orchard full viewing key within this unified full viewing key, if any.
+    pub fn orchard(&self) -> Option<&FullViewingKey> {
+        self.0.iter().find_map(|fvk| match fvk {
+            Fvk::Orchard(fvk) => Some(fvk),
+            _ => None,
+        })
+    }
+
+    /// Encodes this unified full viewing key for the given network.
+    pub fn encode(&self, network: Network) -> String {
+        encode_items(FVK_HRPS.for_network(network), &self.0)
+    }
+
+    /// Decodes a unified full viewing key, returning it along with the network it is for.
+    pub fn decode(s: &str) -> Result<(Network, Self), ParseError> {
+        decode_items(&FVK_HRPS, s).map(|(network, items)| (network, UnifiedFullViewingKey(items)))
+    }
+}
+
+/// A Unified Incoming Viewing Key.
+///
+/// Defined in [ZIP 316: Unified Viewing Keys][unifiedviewingkeys].
+///
+/// [unifiedviewingkeys]: https://zips.z.cash/zip-0316#encoding-of-unified-full-incoming-viewing-keys
+#[derive(Clone, Debug)]
+pub struct UnifiedIncomingViewingKey(Vec<Ivk>);
+
+impl UnifiedIncomingViewingKey {
+    /// Constructs a unified incoming viewing key from the given viewing keys.
+    ///
+    /// The viewing keys are placed in ascending typecode order. Returns an error if the
+    /// viewing keys do not form a valid unified incoming viewing key.
+    pub fn try_from_items(ivks: Vec<Ivk>) -> Result<Self, ParseError> {
+        sort_and_check_items(IVK_HRPS.main, ivks).map(UnifiedIncomingViewingKey)
+    }
+
+    /// Returns the viewing keys within this unified incoming viewing key, in ascending
+    /// typecode order.
+    pub fn items(&self) -> &[Ivk] {
+        &self.0
+    }
+
+    /// Returns the Orchard incoming viewing key within this unified incoming viewing
+    /// key, if any.
+    pub fn orchard(&self) -> Option<&IncomingViewingKey> {
+        self.0.iter().find_map(|ivk| match ivk {
+            Ivk::Orchard(ivk) => Some(ivk),
+            _ => None,
+        })
+    }
+
+    /// Encodes this unified incoming viewing key for the given network.
+    pub fn encode(&self, network: Network) -> String {
+        encode_items(IVK_HRPS.for_network(network), &self.0)
+    }
+
+    /// Decodes a unified incoming viewing key, returning it along with the network it is
+    /// for.
+    pub fn decode(s: &str) -> Result<(Network, Self), ParseError> {
+        decode_items(&IVK_HRPS, s)
+            .map(|(network, items)| (network, UnifiedIncomingViewingKey(items)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{
+        encode_items, Fvk, Item, Ivk, Network, ParseError, Receiver, Typecode, UnifiedAddress,
+        UnifiedFullViewingKey, UnifiedIncomingViewingKey, ADDRESS_HRPS,
+    };
+    use crate::{
+        address::testing::arb_address,
+        keys::{testing::arb_spending_key, FullViewingKey, IncomingViewingKey},
+        Address,
+    };
+
+    fn arb_network() -> impl Strategy<Value = Network> {
+        prop_oneof![
+            Just(Network::Main),
+            Just(Network::Test),
+            Just(Network::Regtest)
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(20))]
+
+        #[test]
+        fn address_roundtrip(
+            addr in arb_address(),
+            sapling in prop::array::uniform32(any::<u8>()),
+            p2pkh in prop::array::uniform20(any::<u8>()),
+            network in arb_network(),
+        ) {
+            let mut sapling_addr = [0; 43];
+            sapling_addr[11..].copy_from_slice(&sapling);
+
+            // Items are sorted into ascending typecode order.
+            let ua = UnifiedAddress::try_from_items(vec![
+                Receiver::Orchard(addr),
+                Receiver::Unknown { typecode: 0x20, data: vec![7; 5] },
+                Receiver::P2pkh(p2pkh),
+                Receiver::Sapling(sapling_addr),
+            ])
+            .unwrap();
+            let typecodes: Vec<_> = ua.items().iter().map(|r| u32::from(r.typecode())).collect();
+            assert_eq!(typecodes, vec![0x00, 0x02, 0x03, 0x20]);
+            assert_eq!(ua.orchard(), Some(&addr));
+
+            let encoded = ua.encode(network);
+            assert_eq!(UnifiedAddress::decode(&encoded), Ok((network, ua)));
+
+            // A unified address is not a valid unified viewing key.
+            assert!(matches!(
+                UnifiedFullViewingKey::decode(&encoded),
+                Err(ParseError::UnknownPrefix(_))
+            ));
+        }
+
+        #[test]
+        fn viewing_key_roundtrip(sk in arb_spending_key(), network in arb_network()) {
+            let fvk = FullViewingKey::from(&sk);
+            let ivk = IncomingViewingKey::from(&fvk);
+
+            let ufvk = UnifiedFullViewingKey::try_from_items(vec![
+                Fvk::P2pkh([1; 65]),
+                Fvk::Orchard(fvk.clone()),
+            ])
+            .unwrap();
+            let (decoded_network, decoded) =
+                UnifiedFullViewingKey::decode(&ufvk.encode(network)).unwrap();
+            assert_eq!(decoded_network, network);
+            assert_eq!(decoded.orchard().unwrap().to_bytes(), fvk.to_bytes());
+            assert!(matches!(decoded.items()[0], Fvk::P2pkh(data) if data == [1; 65]));
+
+            let uivk = UnifiedIncomingViewingKey::try_from_items(vec![
+                Ivk::Orchard(ivk.clone()),
+            ])
+            .unwrap();
+            let (decoded_network, decoded) =
+                UnifiedIncomingViewingKey::decode(&uivk.encode(network)).unwrap();
+            assert_eq!(decoded_network, network);
+            assert_eq!(decoded.orchard().unwrap().to_bytes(), ivk.to_bytes());
+        }
+    }
+
+    #[test]
+    fn address_test_vectors() {
+        let test_vectors = crate::test_vectors::unified::address_test_vectors();
+        assert!(!test_vectors.is_empty(), "missing ZIP 316 test vectors");
+
+        for tv in test_vectors {
+            let mut receivers = vec![];
+            if let Some(data) = tv.p2pkh_bytes {
+                receivers.push(Receiver::P2pkh(data));
+            }
+            if let Some(data) = tv.p2sh_bytes {
+                receivers.push(Receiver::P2sh(data));
+            }
+            if let Some(data) = tv.sapling_raw_addr {
+                receivers.push(Receiver::Sapling(data));
+            }
+            if let Some(data) = tv.orchard_raw_addr {
+                receivers.push(Receiver::Orchard(
+                    Address::from_raw_address_bytes(&data).unwrap(),
+                ));
+            }
+            if let (Some(typecode), Some(data)) = (tv.unknown_typecode, tv.unknown_bytes) {
+                receivers.push(Receiver::Unknown {
+                    typecode,
+                    data: data.to_vec(),
+                });
+            }
+
+            let ua = UnifiedAddress::try_from_items(receivers).unwrap();
+            assert_eq!(ua.encode(Network::Main), tv.unified_addr);
+            assert_eq!(
+                UnifiedAddress::decode(tv.unified_addr),
+                Ok((Network::Main, ua))
+            );
+        }
+    }
+
+    #[test]
+    fn fvk_test_vectors() {
+        let test_vectors = crate::test_vectors::unified::fvk_test_vectors();
+        assert!(!test_vectors.is_empty(), "missing ZIP 316 test vectors");
+
+        for tv in test_vectors {
+            let mut fvks = vec![];
+            if let Some(data) = tv.t_key_bytes {
+                fvks.push(Fvk::P2pkh(data));
+            }
+            if let Some(data) = tv.sapling_fvk_bytes {
+                fvks.push(Fvk::Sapling(data));
+            }
+            if let Some(data) = tv.orchard_fvk_bytes {
+                fvks.push(Fvk::Orchard(FullViewingKey::from_bytes(&data).unwrap()));
+            }
+            if let (Some(typecode), Some(data)) = (tv.unknown_fvk_typecode, tv.unknown_fvk_bytes) {
+                fvks.push(Fvk::Unknown {
+                    typecode,
+                    data: data.to_vec(),
+                });
+            }
+
+            let ufvk = UnifiedFullViewingKey::try_from_items(fvks).unwrap();
+            assert_eq!(ufvk.encode(Network::Main), tv.unified_fvk);
+            let (network, decoded) = UnifiedFullViewingKey::decode(tv.unified_fvk).unwrap();
+            assert_eq!(network, Network::Main);
+            assert_eq!(
+                decoded.orchard().map(|fvk| fvk.to_bytes()),
+                tv.orchard_fvk_bytes
+            );
+            assert_eq!(decoded.encode(Network::Main), tv.unified_fvk);
+        }
+    }
+
+    #[test]
+    fn ivk_test_vectors() {
+        let test_vectors = crate::test_vectors::unified::ivk_test_vectors();
+        assert!(!test_vectors.is_empty(), "missing ZIP 316 test vectors");
+
+        for tv in test_vectors {
+            let mut ivks = vec![];
+            if let Some(data) = tv.t_key_bytes {
+                ivks.push(Ivk::P2pkh(data));
+            }
+            if let Some(data) = tv.sapling_ivk_bytes {
+                ivks.push(Ivk::Sapling(data));
+            }
+            if let Some(data) = tv.orchard_ivk_bytes {
+                ivks.push(Ivk::Orchard(IncomingViewingKey::from_bytes(&data).unwrap()));
+            }
+            if let (Some(typecode), Some(data)) = (tv.unknown_ivk_typecode, tv.unknown_ivk_bytes) {
+                ivks.push(Ivk::Unknown {
+                    typecode,
+                    data: data.to_vec(),
+                });
+            }
+
+            let uivk = UnifiedIncomingViewingKey::try_from_items(ivks).unwrap();
+            assert_eq!(uivk.encode(Network::Main), tv.unified_ivk);
+            let (network, decoded) = UnifiedIncomingViewingKey::decode(tv.unified_ivk).unwrap();
+            assert_eq!(network, Network::Main);
+            assert_eq!(
+                decoded.orchard().map(|ivk| ivk.to_bytes()),
+                tv.orchard_ivk_bytes
+            );
+            assert_eq!(decoded.encode(Network::Main), tv.unified_ivk);
+        }
+    }
+
+    #[test]
+    fn invalid_containers() {
+        let sapling = Receiver::Sapling([0; 43]);
+
+        assert_eq!(
+            UnifiedAddress::try_from_items(vec![Receiver::P2pkh([0; 20])]),
+            Err(ParseError::OnlyTransparent),
+        );
+        assert_eq!(
+            UnifiedAddress::try_from_items(vec![
+                Receiver::P2pkh([0; 20]),
+                Receiver::P2sh([0; 20]),
+                sapling.clone(),
+            ]),
+            Err(ParseError::BothP2phkAndP2sh),
+        );
+        assert_eq!(
+            UnifiedAddress::try_from_items(vec![sapling.clone(), sapling.clone()]),
+            Err(ParseError::DuplicateTypecode(Typecode::Sapling)),
+        );
+        assert_eq!(
+            UnifiedAddress::try_from_items(vec![
+                sapling.clone(),
+                Receiver::Unknown {
+                    typecode: 0x03,
+                    data: vec![0; 42],
+                },
+            ]),
+            Err(ParseError::InvalidItem(Typecode::Orchard)),
+        );
+
+        // Items that are not in ascending typecode order are rejected when decoding.
+        let encoded = encode_items(
+            ADDRESS_HRPS.main,
+            &[sapling.clone(), Receiver::P2pkh([0; 20])],
+        );
+        assert_eq!(
+            UnifiedAddress::decode(&encoded),
+            Err(ParseError::InvalidTypecodeOrder),
+        );
+
+        // Items with the wrong length are rejected when decoding.
+        let encoded = encode_items(
+            ADDRESS_HRPS.main,
+            &[
+                sapling,
+                Receiver::Unknown {
+                    typecode: 0x03,
+                    data: vec![0; 42],
+                },
+            ],
+        );
+        assert_eq!(
+            UnifiedAddress::decode(&encoded),
+            Err(ParseError::InvalidItem(Typecode::Orchard)),
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! The F4Jumble unkeyed 4-round Feistel construction.
+//!
+//! Defined in [ZIP 316: Jumbling][jumbling].
+//!
+//! [jumbling]: https://zips.z.cash/zip-0316#jumbling
+
+use std::cmp::min;
+use std::ops::RangeInclusive;
+
+use blake2b_simd::{Params as Blake2bParams, OUTBYTES};
+
+const H_PERSONALIZATION_PREFIX: &[u8; 13] = b"UA_F4Jumble_H";
+const G_PERSONALIZATION_PREFIX: &[u8; 13] = b"UA_F4Jumble_G";
+
+/// The valid lengths of an F4Jumble input, in bytes.
+pub(super) const VALID_LENGTH: RangeInclusive<usize> = 48..=4194368;
+
+struct State<'a> {
+    left: &'a mut [u8],
+    right: &'a mut [u8],
+}
+
+impl<'a> State<'a> {
+    fn new(message: &'a mut [u8]) -> Self {
+        let left_length = min(OUTBYTES, message.len() / 2);
+        let (left, right) = message.split_at_mut(left_length);
+        State { left, right }
+    }
+
+    /// $H_i$: XORs the left half with a hash of the right half.
+    fn h_round(&mut self, i: u8) {
+        let mut personal = [0; 16];
+        personal[..13].copy_from_slice(H_PERSONALIZATION_PREFIX);
+        personal[13] = i;
+
+        let hash = Blake2bParams::new()
+            .hash_length(self.left.len())
+            .personal(&personal)
+            .hash(self.right);
+        xor(self.left, hash.as_bytes())
+    }
+
+    /// $G_i$: XORs the right half with an expansion of the left half.
+    fn g_round(&mut self, i: u8) {
+        for (j, chunk) in self.right.chunks_mut(OUTBYTES).enumerate() {
+            let mut personal = [0; 16];
+            personal[..13].copy_from_slice(G_PERSONALIZATION_PREFIX);
+            personal[13] = i;
+            personal[14..].copy_from_slice(&(j as u16).to_le_bytes());
+
+            let hash = Blake2bParams::new()
+                .hash_length(OUTBYTES)
+                .personal(&personal)
+                .hash(self.left);
+            xor(chunk, hash.as_bytes());
+        }
+    }
+
+    fn apply_f4jumble(&mut self) {
+        self.g_round(0);
+        self.h_round(0);
+        self.g_round(1);
+        self.h_round(1);
+    }
+
+    fn apply_f4jumble_inv(&mut self) {
+        self.h_round(1);
+        self.g_round(1);
+        self.h_round(0);
+        self.g_round(0);
+    }
+}
+
+/// XORs bytes of `source` into bytes of `target`, stopping at the shorter of the two.
+fn xor(target: &mut [u8], source: &[u8]) {
+    for (t, s) in target.iter_mut().zip(source.iter()) {
+        *t ^= s;
+    }
+}
+
+/// Encodes the given message using F4Jumble.
+///
+/// Returns `None` if the message length is not in [`VALID_LENGTH`].
+pub(super) fn f4jumble(message: &[u8]) -> Option<Vec<u8>> {
+    if VALID_LENGTH.contains(&message.len()) {
+        let mut result = message.to_vec();
+        State::new(&mut result).apply_f4jumble();
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Decodes the given message using F4Jumble⁻¹.
+///
+/// Returns `None` if the message length is not in [`VALID_LENGTH`].
+pub(super) fn f4jumble_inv(message: &[u8]) -> Option<Vec<u8>> {
+    if VALID_LENGTH.contains(&message.len()) {
+        let mut result = message.to_vec();
+        State::new(&mut result).apply_f4jumble_inv();
+        Some(result)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use super::{f4jumble, f4jumble_inv, VALID_LENGTH};
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(50))]
+
+        #[test]
+        fn f4jumble_roundtrip(msg in vec(any::<u8>(), 48..400)) {
+            let jumbled = f4jumble(&msg).unwrap();
+            assert_eq!(jumbled.len(), msg.len());
+            assert_ne!(jumbled, msg);
+            assert_eq!(f4jumble_inv(&jumbled).unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn f4jumble_test_vectors() {
+        let test_vectors = crate::test_vectors::f4jumble::test_vectors();
+        assert!(!test_vectors.is_empty(), "missing ZIP 316 test vectors");
+
+        for tv in test_vectors {
+            assert_eq!(f4jumble(tv.normal).unwrap(), tv.jumbled);
+            assert_eq!(f4jumble_inv(tv.jumbled).unwrap(), tv.normal);
+        }
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        assert!(f4jumble(&[0; 47]).is_none());
+        assert!(f4jumble_inv(&[0; 47]).is_none());
+        assert!(f4jumble(&vec![0; VALID_LENGTH.end() + 1]).is_none());
+        assert!(f4jumble_inv(&vec![0; VALID_LENGTH.end() + 1]).is_none());
+    }
+}
@@ -126,6 +126,11 @@ impl ValueSum {
     pub fn from_raw(value: i64) -> Self {
         ValueSum(value as i128)
     }
+
+    /// Returns this value sum as a [`NoteValue`], or `None` if it is negative.
+    pub(crate) fn to_note_value(self) -> Option<NoteValue> {
+        u64::try_from(self.0).ok().map(NoteValue)
+    }
 }
 
 impl Add for ValueSum {
@@ -140,6 +145,18 @@ impl Add for ValueSum {
     }
 }
 
+impl Sub for ValueSum {
+    type Output = Option<ValueSum>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.0
+            .checked_sub(rhs.0)
+            .filter(|v| VALUE_SUM_RANGE.contains(v))
+            .map(ValueSum)
+    }
+}
+
 impl<'a> Sum<&'a ValueSum> for Result<ValueSum, OverflowError> {
     fn sum<I: Iterator<Item = &'a ValueSum>>(iter: I) -> Self {
         iter.fold(Ok(ValueSum(0)), |acc, v| (acc? + *v).ok_or(OverflowError))
@@ -168,6 +185,16 @@ impl ValueCommitTrapdoor {
     pub(crate) fn inner(&self) -> pallas::Scalar {
         self.0
     }
+
+    /// Parses a value commitment trapdoor from its canonical byte encoding.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> CtOption<Self> {
+        pallas::Scalar::from_bytes(bytes).map(ValueCommitTrapdoor)
+    }
+
+    /// Returns the canonical byte encoding of this value commitment trapdoor.
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
 }
 
 impl Add<&ValueCommitTrapdoor> for ValueCommitTrapdoor {
@@ -0,0 +1,364 @@
+//! Key structures for Orchard hierarchical deterministic key derivation.
+//!
+//! Defined in [ZIP 32: Shielded Hierarchical Deterministic Wallets][zip32].
+//!
+//! [zip32]: https://zips.z.cash/zip-0032
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+};
+
+use blake2b_simd::Params as Blake2bParams;
+
+use crate::{
+    keys::{FullViewingKey, SpendingKey},
+    spec::PrfExpand,
+};
+
+const ZIP32_ORCHARD_PERSONALIZATION: &[u8; 16] = b"ZcashIP32Orchard";
+const ZIP32_ORCHARD_FVFP_PERSONALIZATION: &[u8; 16] = b"ZcashOrchardFVFP";
+
+/// Errors produced in derivation of extended spending keys.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A seed resulted in an invalid spending key.
+    InvalidSpendingKey,
+    /// A seed was shorter than 32 bytes or longer than 252 bytes.
+    InvalidSeedLength(usize),
+    /// A child index in a derivation path exceeded 2^31.
+    InvalidChildIndex(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSpendingKey => write!(f, "Seed produced an invalid spending key"),
+            Error::InvalidSeedLength(len) => {
+                write!(f, "Seed must be between 32 and 252 bytes, got {}", len)
+            }
+            Error::InvalidChildIndex(i) => write!(f, "Child index {} must be less than 2^31", i),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// An Orchard full viewing key fingerprint.
+struct FvkFingerprint([u8; 32]);
+
+impl From<&FullViewingKey> for FvkFingerprint {
+    fn from(fvk: &FullViewingKey) -> Self {
+        let mut h = Blake2bParams::new()
+            .hash_length(32)
+            .personal(ZIP32_ORCHARD_FVFP_PERSONALIZATION)
+            .to_state();
+        h.update(&fvk.to_bytes());
+        FvkFingerprint(h.finalize().as_bytes().try_into().unwrap())
+    }
+}
+
+impl FvkFingerprint {
+    fn tag(&self) -> FvkTag {
+        FvkTag(self.0[..4].try_into().unwrap())
+    }
+}
+
+/// An Orchard full viewing key tag.
+///
+/// This is the first four bytes of the fingerprint of a parent key's full viewing key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FvkTag([u8; 4]);
+
+impl FvkTag {
+    /// The tag used for the master extended spending key, which has no parent.
+    fn master() -> Self {
+        FvkTag([0u8; 4])
+    }
+
+    /// Returns the raw bytes of this tag.
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+}
+
+/// A hardened child index for a derived key.
+///
+/// Orchard only supports hardened derivation, so the index is always stored with the
+/// hardened bit set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChildIndex(u32);
+
+impl TryFrom<u32> for ChildIndex {
+    type Error = Error;
+
+    /// `index` must be less than 2^31.
+    fn try_from(index: u32) -> Result<Self, Self::Error> {
+        if index < (1 << 31) {
+            Ok(ChildIndex(index + (1 << 31)))
+        } else {
+            Err(Error::InvalidChildIndex(index))
+        }
+    }
+}
+
+impl ChildIndex {
+    /// Returns the index with the hardened bit set, as used in the derivation.
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+/// The chain code forming the second half of an Orchard extended key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainCode([u8; 32]);
+
+impl ChainCode {
+    /// Returns the raw bytes of this chain code.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// An Orchard extended spending key.
+///
+/// Defined in [ZIP 32: Orchard extended keys][orchardextendedkeys].
+///
+/// [orchardextendedkeys]: https://zips.z.cash/zip-0032#orchard-extended-keys
+#[derive(Debug, Clone)]
+pub struct ExtendedSpendingKey {
+    depth: u8,
+    parent_fvk_tag: FvkTag,
+    child_index: ChildIndex,
+    chain_code: ChainCode,
+    sk: SpendingKey,
+}
+
+impl ExtendedSpendingKey {
+    /// Derives the extended spending key at the given path, starting from the master
+    /// key for the given seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the derivation path is longer than 255 elements.
+    pub fn from_path(seed: &[u8], path: &[ChildIndex]) -> Result<Self, Error> {
+        path.iter()
+            .try_fold(Self::master(seed)?, |xsk, i| xsk.derive_child(*i))
+    }
+
+    /// Generates the master extended spending key from the given seed.
+    ///
+    /// Defined in [ZIP 32: Orchard master key generation][orchardmasterkey].
+    ///
+    /// [orchardmasterkey]: https://zips.z.cash/zip-0032#orchard-master-key-generation
+    pub fn master(seed: &[u8]) -> Result<Self, Error> {
+        if seed.len() < 32 || seed.len() > 252 {
+            return Err(Error::InvalidSeedLength(seed.len()));
+        }
+
+        // I := BLAKE2b-512("ZcashIP32Orchard", seed)
+        let i: [u8; 64] = {
+            let mut h = Blake2bParams::new()
+                .hash_length(64)
+                .personal(ZIP32_ORCHARD_PERSONALIZATION)
+                .to_state();
+            h.update(seed);
+            *h.finalize().as_array()
+        };
+
+        // I_L is used as the master spending key sk_m.
+        let sk_m = SpendingKey::from_bytes(i[..32].try_into().unwrap());
+        if sk_m.is_none().into() {
+            return Err(Error::InvalidSpendingKey);
+        }
+
+        // I_R is used as the master chain code c_m.
+        let c_m = ChainCode(i[32..].try_into().unwrap());
+
+        // For the master extended spending key, depth is 0, parent_fvk_tag is 4 zero
+        // bytes, and i is 0.
+        Ok(ExtendedSpendingKey {
+            depth: 0,
+            parent_fvk_tag: FvkTag::master(),
+            child_index: ChildIndex(0),
+            chain_code: c_m,
+            sk: sk_m.unwrap(),
+        })
+    }
+
+    /// Derives a child key from a parent key at a given index.
+    ///
+    /// Defined in [ZIP 32: Orchard child key derivation][orchardchildkey].
+    ///
+    /// [orchardchildkey]: https://zips.z.cash/zip-0032#orchard-child-key-derivation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parent key is at depth 255.
+    pub fn derive_child(&self, index: ChildIndex) -> Result<Self, Error> {
+        // I := PRF^Expand(c_par, [0x81] || sk_par || I2LEOSP(i))
+        let i = PrfExpand::OrchardZip32Child.with_ad_slices(
+            &self.chain_code.0,
+            &[&self.sk.to_bytes()[..], &index.0.to_le_bytes()[..]],
+        );
+
+        // I_L is used as the child spending key sk_i.
+        let sk_i = SpendingKey::from_bytes(i[..32].try_into().unwrap());
+        if sk_i.is_none().into() {
+            return Err(Error::InvalidSpendingKey);
+        }
+
+        // I_R is used as the child chain code c_i.
+        let c_i = ChainCode(i[32..].try_into().unwrap());
+
+        let fvk = FullViewingKey::from(&self.sk);
+
+        Ok(ExtendedSpendingKey {
+            depth: self.depth.checked_add(1).expect("derivation path too long"),
+            parent_fvk_tag: FvkFingerprint::from(&fvk).tag(),
+            child_index: index,
+            chain_code: c_i,
+            sk: sk_i.unwrap(),
+        })
+    }
+
+    /// Returns the depth of this key in the derivation tree.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Returns the tag of the parent key's full viewing key.
+    pub fn parent_fvk_tag(&self) -> &FvkTag {
+        &self.parent_fvk_tag
+    }
+
+    /// Returns the index at which this key was derived from its parent.
+    pub fn child_index(&self) -> ChildIndex {
+        self.child_index
+    }
+
+    /// Returns the chain code for this key.
+    pub fn chain_code(&self) -> &ChainCode {
+        &self.chain_code
+    }
+
+    /// Returns the spending key for this extended key.
+    pub fn sk(&self) -> SpendingKey {
+        self.sk.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::{TryFrom, TryInto};
+
+    use super::{ChildIndex, Error, ExtendedSpendingKey, FvkFingerprint};
+    use crate::keys::{FullViewingKey, SpendingKey};
+
+    #[test]
+    fn derive_child() {
+        let seed = [0; 32];
+        let xsk_m = ExtendedSpendingKey::master(&seed).unwrap();
+
+        let i_5 = ChildIndex::try_from(5).unwrap();
+        let xsk_5 = xsk_m.derive_child(i_5).unwrap();
+        assert_eq!(xsk_5.depth(), 1);
+        assert_eq!(xsk_5.child_index(), i_5);
+
+        assert_eq!(
+            ExtendedSpendingKey::from_path(&seed, &[i_5])
+                .unwrap()
+                .sk()
+                .to_bytes(),
+            xsk_5.sk().to_bytes(),
+        );
+    }
+
+    #[test]
+    fn path() {
+        let seed = [0; 32];
+        let xsk_m = ExtendedSpendingKey::master(&seed).unwrap();
+
+        let xsk_5h = xsk_m
+            .derive_child(ChildIndex::try_from(5).unwrap())
+            .unwrap();
+        let xsk_5h_7h = xsk_5h
+            .derive_child(ChildIndex::try_from(7).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            ExtendedSpendingKey::from_path(
+                &seed,
+                &[
+                    ChildIndex::try_from(5).unwrap(),
+                    ChildIndex::try_from(7).unwrap()
+                ]
+            )
+            .unwrap()
+            .sk()
+            .to_bytes(),
+            xsk_5h_7h.sk().to_bytes()
+        );
+        assert_eq!(
+            SpendingKey::from_zip32_seed(&seed, 7, 9)
+                .unwrap()
+                .to_bytes(),
+            ExtendedSpendingKey::from_path(
+                &seed,
+                &[
+                    ChildIndex::try_from(32).unwrap(),
+                    ChildIndex::try_from(7).unwrap(),
+                    ChildIndex::try_from(9).unwrap(),
+                ]
+            )
+            .unwrap()
+            .sk()
+            .to_bytes()
+        );
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        assert_eq!(
+            ChildIndex::try_from(1 << 31),
+            Err(Error::InvalidChildIndex(1 << 31))
+        );
+        assert_eq!(
+            ExtendedSpendingKey::master(&[0; 31]).unwrap_err(),
+            Error::InvalidSeedLength(31)
+        );
+        assert_eq!(
+            ExtendedSpendingKey::master(&[0; 253]).unwrap_err(),
+            Error::InvalidSeedLength(253)
+        );
+    }
+
+    #[test]
+    fn test_vectors() {
+        let test_vectors = crate::test_vectors::zip32::test_vectors();
+        let seed: Vec<u8> = (0..32).collect();
+
+        let mut xsk = ExtendedSpendingKey::master(&seed).unwrap();
+        for (i, tv) in test_vectors.iter().enumerate() {
+            if i > 0 {
+                xsk = xsk
+                    .derive_child(ChildIndex::try_from(i as u32).unwrap())
+                    .unwrap();
+            }
+            assert_eq!(xsk.depth() as usize, i);
+            assert_eq!(xsk.sk().to_bytes(), &tv.sk);
+            assert_eq!(xsk.chain_code().as_bytes(), &tv.c);
+
+            let fvk = FullViewingKey::from(&xsk.sk());
+            assert_eq!(FvkFingerprint::from(&fvk).0, tv.fp);
+
+            // The tag is the first four bytes of the parent's FVK fingerprint.
+            let expected_tag: [u8; 4] = if i == 0 {
+                [0; 4]
+            } else {
+                test_vectors[i - 1].fp[..4].try_into().unwrap()
+            };
+            assert_eq!(xsk.parent_fvk_tag().as_bytes(), &expected_tag);
+        }
+    }
+}